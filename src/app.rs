@@ -1,8 +1,10 @@
 // ==================== 标准库导入 ====================
-use std::collections::HashSet; // 哈希集合，用于存储不重复的标签
+use std::collections::{BTreeMap, BTreeSet, HashSet}; // 映射和集合，用于存储自定义字段、不重复的标签和情境
 use std::fs::File; // 文件操作
 use std::io; // 输入输出
 use std::io::BufReader; // 带缓冲的读取器，用于读取音频文件
+use std::ops::Range; // 连续的行范围
+use std::path::PathBuf; // 文件路径
 use std::sync::{Arc, Mutex}; // 线程安全的共享所有权和互斥锁
 
 // ==================== 第三方库导入 ====================
@@ -10,7 +12,7 @@ use std::sync::{Arc, Mutex}; // 线程安全的共享所有权和互斥锁
 use time::{Duration, OffsetDateTime}; // 使用 time 进行日期时间处理
 
 // 终端事件处理
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind}; // 键盘事件
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers}; // 键盘事件
 
 // TUI 渲染
 use ratatui::{
@@ -27,6 +29,11 @@ use walkdir::WalkDir; // 递归遍历目录
 
 // ==================== 项目内部模块导入 ====================
 // UI 渲染模块
+use crate::clipboard; // 通过 OSC 52 写入系统剪贴板
+use crate::config::Config; // 用户配置
+use crate::editor::{self, EditorTarget}; // 外部编辑器
+use crate::markdown; // Markdown 解析（复选框切换）
+use crate::opener; // 用外部程序打开链接
 use crate::ui; // 界面渲染逻辑
 
 // 数据模型模块 - 按功能分组
@@ -49,14 +56,161 @@ use crate::models::{
     WindowType,   // 窗口类型
 };
 
+// 输入窗口与状态栏
+use crate::models::{
+    ConfirmAction, // 需要确认的操作
+    InputKind,     // 输入窗口用途
+    StatusMessage, // 状态栏提示
+    TaskLink,      // 任务链接
+    TextInput,     // 文本输入框
+};
+
+// 番茄钟与计时
+use crate::models::{
+    DEFAULT_POMODORO_MINUTES, // 默认番茄钟时长
+    Estimate,                 // 预估用时
+    POMODORO_MINUTES_RANGE,   // 可以设置的番茄钟时长
+    POMODORO_PRESETS,         // 常用番茄钟时长
+    PomodoroSession,          // 番茄钟
+    format_duration,          // 时长格式化
+    parse_progress,           // 进度解析
+};
+
+// 排序与自定义字段
+use crate::models::{
+    FieldFilter, // 自定义字段筛选
+    Movement,    // 手动排序的移动方向
+    Priority,    // 优先级
+    SortContext, // 排序上下文
+    SortMode,    // 列表排序方式
+    Urgency,     // 紧急度
+};
+
+// 日期与计划
+use crate::models::{
+    Activity,        // 任务历史
+    ActivityKind,    // 历史记录类型
+    DateShift,       // 批量调整截止日期
+    DeferPreset,     // 推迟预设
+    TagChanges,      // 批量修改标签
+    format_datetime, // 日期格式化
+    parse_due,       // 截止日期解析
+};
+
+// 模式
+use crate::models::{
+    AppMode,        // 应用模式
+    ModeAwareEvent, // 模式感知的事件
+    ModeHandler,    // 按模式处理事件
+    ModeManager,    // 模式管理器
+};
+
+// 命令行
+use crate::models::{
+    CommandKind,   // 命令种类
+    Completion,    // Tab 补全状态
+    ExCommand,     // 解析后的命令
+    LineRange,     // 命令的行范围
+    SET_FLAGS,     // :set 开关选项
+    common_prefix, // 候选项的公共前缀
+    complete_line, // 命令行补全
+};
+
+// 按键映射与宏
+use crate::models::{
+    Action,      // 可以绑定按键的动作
+    Change,      // 可以用 . 重复的修改
+    KeyOutcome,  // 按键序列的处理结果
+    KeyPress,    // 一次按键
+    PendingKeys, // 尚未输入完整的按键序列
+    format_keys, // 按键序列的 Vim 写法
+    parse_keys,  // 解析 Vim 写法的按键序列
+};
+
+// 复制与粘贴
+use crate::models::{
+    RegisterContent,  // 寄存器内容
+    Registers,        // 复制粘贴用的寄存器
+    UNNAMED_REGISTER, // 无名寄存器
+};
+
+// 帮助
+use crate::models::{
+    DETAILS_KEYS,    // 详情面板的按键
+    HELP_KEYS,       // 帮助浮层的按键
+    HelpSection,     // 帮助中的一组按键
+    HelpState,       // 帮助浮层的状态
+    PREFIX_KEYS,     // 计数和寄存器前缀
+    REPEAT_KEYS,     // . 能重复的修改
+    TEXT_INPUT_KEYS, // 输入框的编辑按键
+};
+
+// 收件箱与情境
+use crate::models::{
+    InboxStep,        // 收件箱处理步骤
+    extract_contexts, // 从快速记录中取出情境
+};
+
+/// 最多保留的历史命令条数
+const COMMAND_HISTORY_LIMIT: usize = 100;
+
+/// 带计数新建任务（`p`、`D`）时最多重复的次数
+const CREATE_LIMIT: usize = 100;
+
+/// 宏最多嵌套执行的层数，避免宏无限调用自己
+const MACRO_DEPTH_LIMIT: usize = 20;
+
+/// 一次执行宏（包括嵌套执行的宏）最多送出的按键数，避免很大的计数让界面卡住
+const MACRO_KEY_LIMIT: usize = 100_000;
+
 pub struct App {
     // 是否退出
     pub exit: bool,
+    pub config: Config,       // 用户配置
     pub show_dashboard: bool, // 新增：控制是否显示启动界面
     pub tasks: Vec<TodoTask>,
+    pub view: Vec<usize>,            // 列表中显示的任务在 tasks 中的下标
+    pub show_future: bool,           // 是否显示尚未开始的任务
+    pub sort: SortMode,              // 列表排序方式
+    pub filter: Option<FieldFilter>, // 自定义字段筛选
+    pub only_mine: bool,             // 只显示分配给本机用户的任务
+    pub show_numbers: bool,          // 是否在列表中显示行号（:set number）
+    // 模式
+    pub mode: ModeManager,       // 当前模式
+    pub pending: PendingKeys,    // 普通模式中尚未输入完整的按键序列
+    last_change: Option<Change>, // 最近一次修改，用 . 重复
+    pub prompt: TextInput,       // 命令模式和搜索模式的输入行
+    // 命令行
+    pub completion: Option<Completion>, // Tab 补全的候选项
+    command_history: Vec<String>,       // 执行过的命令，最新的在最后
+    history_index: Option<usize>,       // 正在浏览的历史命令
+    write_path: Option<PathBuf>,        // :w 上次写入的文件
+    // 搜索
+    pub search_query: Option<String>, // 上次搜索的关键字，用于 n/N 和高亮
+    search_origin: Option<u64>,       // 开始搜索时选中的任务，取消时回到这里
+    // 可视模式与批量操作
+    pub visual_anchor: Option<u64>, // 可视模式开始时所在的任务
+    registers: Registers,           // 复制和删除的任务与文本，以及录制的宏
+    recording: Option<(char, Vec<KeyPress>)>, // 正在录制的宏：寄存器和录下的按键
+    sequence_start: usize,          // 录制宏时当前按键序列的第一个键在录下的按键中的位置
+    last_macro: Option<char>,       // 上次执行的宏，g@@ 重复执行
+    macro_depth: usize,             // 正在执行的宏的嵌套层数
+    macro_keys: usize,              // 本次执行宏已经送出的按键数
+    targets: Vec<u64>,              // 等待输入的批量操作所针对的任务
     pub list_state: ListState,
     pub active_window: Option<ActiveWindow>,
     pub scroll_state: ScrollbarState,
+    pub help: Option<HelpState>, // 打开的帮助浮层
+    // 详情面板
+    pub details_focused: bool,  // 详情面板是否获得焦点
+    pub checkbox_cursor: usize, // 详情面板中选中的复选框序号
+    // 番茄钟
+    pub pomodoro: Option<PomodoroSession>, // 正在进行的番茄钟
+    pub pomodoro_minutes: u32,             // 番茄钟时长（分钟）
+    // 状态栏与外部编辑器
+    pub status_message: Option<StatusMessage>, // 状态栏提示信息
+    pending_editor: Option<EditorTarget>,      // 下一轮循环中要打开的编辑器
+    editor_draft: Option<(usize, String)>,     // 解析失败时保留的编辑内容
     // 音乐
     pub music_scroll_state: ScrollbarState, // 新增：音乐列表滚动条
     pub music_files: Vec<AudioFileInfo>,
@@ -72,11 +226,12 @@ pub struct App {
 impl Default for App {
     fn default() -> Self {
         // 1. 准备一些初始数据（可选，方便你调试界面）
-        let tasks = vec![
+        let mut tasks = vec![
             // 测试用例1：未完成的代码任务
             TodoTask {
                 title: "写代码".into(),
-                description: "使用 Rust 和 Ratatui 编写 TUI 应用".into(),
+                list: Some("工作".into()),
+                description: "## 目标\n使用 **Rust** 和 *Ratatui* 编写 TUI 应用\n\n- [x] 搭建项目骨架\n- [ ] 实现 `draw_details`\n- [ ] 支持 [Markdown](https://commonmark.org) 渲染\n\n```rust\nratatui::run(|terminal| App::default().run(terminal))\n```".into(),
                 status: TaskStatus::Todo,
                 tags: {
                     let mut tags = HashSet::new();
@@ -87,10 +242,18 @@ impl Default for App {
                 created_at: OffsetDateTime::now_utc() - Duration::days(2), // 2天前创建
                 due_date: Some(OffsetDateTime::now_utc() + Duration::days(5)), // 5天后截止
                 finish_date: None,
+                estimate: Some(Estimate::Pomodoros(4)),
+                time_spent: Duration::minutes(50),
+                links: vec![
+                    TaskLink::Url("https://ratatui.rs".into()),
+                    TaskLink::Task(3), // 关联"调试程序"
+                ],
+                ..Default::default()
             },
             // 测试用例2：已完成的任务
             TodoTask {
                 title: "去运动".into(),
+                list: Some("生活".into()),
                 description: "跑 5 公里，呼吸新鲜空气".into(),
                 status: TaskStatus::Completed,
                 tags: {
@@ -102,12 +265,16 @@ impl Default for App {
                 created_at: OffsetDateTime::now_utc() - Duration::days(3), // 3天前创建
                 due_date: Some(OffsetDateTime::now_utc() - Duration::days(1)), // 昨天截止（但已完成）
                 finish_date: Some(OffsetDateTime::now_utc() - Duration::days(1)), // 昨天完成
+                ..Default::default()
             },
             // 测试用例3：另一个未完成的代码任务
             TodoTask {
                 title: "调试程序".into(),
+                list: Some("工作".into()),
+                contexts: BTreeSet::from(["office".to_string()]),
                 description: "修复 TUI 应用中的渲染 bug".into(),
                 status: TaskStatus::Todo,
+                priority: Some(Priority::Medium),
                 tags: {
                     let mut tags = HashSet::new();
                     tags.insert(Tag::new("编程".to_string()));
@@ -117,6 +284,9 @@ impl Default for App {
                 created_at: OffsetDateTime::now_utc() - Duration::hours(5), // 5小时前创建
                 due_date: Some(OffsetDateTime::now_utc() + Duration::hours(3)), // 3小时后截止
                 finish_date: None,
+                estimate: Some(Estimate::Minutes(60)),
+                time_spent: Duration::minutes(75), // 已超出预估
+                ..Default::default()
             },
             // 测试用例4：今日到期的任务
             TodoTask {
@@ -131,12 +301,16 @@ impl Default for App {
                 created_at: OffsetDateTime::now_utc() - Duration::days(1), // 1天前创建
                 due_date: Some(OffsetDateTime::now_utc() + Duration::hours(5)), // 今天截止
                 finish_date: None,
+                ..Default::default()
             },
             // 测试用例5：已逾期的任务
             TodoTask {
                 title: "交水电费".into(),
+                list: Some("生活".into()),
+                contexts: BTreeSet::from(["phone".to_string()]),
                 description: "否则会断水断电".into(),
                 status: TaskStatus::Overdue,
+                priority: Some(Priority::High),
                 tags: {
                     let mut tags = HashSet::new();
                     tags.insert(Tag::new("生活".to_string()));
@@ -146,117 +320,1493 @@ impl Default for App {
                 created_at: OffsetDateTime::now_utc() - Duration::days(7), // 7天前创建
                 due_date: Some(OffsetDateTime::now_utc() - Duration::days(2)), // 2天前截止
                 finish_date: None,
+                ..Default::default()
+            },
+            // 测试用例6：计划今天稍后开始的任务
+            TodoTask {
+                title: "周会准备".into(),
+                list: Some("工作".into()),
+                description: "整理本周进展和风险".into(),
+                scheduled_date: Some(OffsetDateTime::now_utc() + Duration::hours(2)), // 2小时后开始
+                ..Default::default()
+            },
+            // 测试用例7：下周才开始的任务（默认隐藏）
+            TodoTask {
+                title: "季度复盘".into(),
+                list: Some("工作".into()),
+                description: "回顾本季度目标完成情况".into(),
+                scheduled_date: Some(OffsetDateTime::now_utc() + Duration::days(7)), // 7天后开始
+                due_date: Some(OffsetDateTime::now_utc() + Duration::days(10)), // 10天后截止
+                ..Default::default()
+            },
+            // 测试用例8：快速记录到收件箱、尚未处理的想法
+            TodoTask {
+                title: "问问房东能不能养猫".into(),
+                created_at: OffsetDateTime::now_utc() - Duration::hours(1), // 1小时前记录
+                contexts: BTreeSet::from(["phone".to_string()]),
+                ..Default::default()
             },
         ];
 
+        // 为示例任务分配 ID，历史从各自的创建时间开始
+        for (i, task) in tasks.iter_mut().enumerate() {
+            task.id = i as u64 + 1;
+            task.position = task.id;
+            task.history = vec![Activity {
+                at: task.created_at,
+                kind: ActivityKind::Created,
+            }];
+            if let Some(finish) = task.finish_date {
+                task.history.push(Activity {
+                    at: finish,
+                    kind: ActivityKind::Completed,
+                });
+            }
+        }
+
+        // 读取配置，出错时在状态栏提示
+        let (config, config_error) = Config::load();
+        let config_error = config_error.map(StatusMessage::error);
+
+        // 配置中的宏放入对应的寄存器
+        let mut registers = Registers::default();
+        for (name, keys) in &config.macros {
+            registers.store_macro(*name, keys.clone());
+        }
+
         // 2. 初始化 ListState
         let mut list_state = ListState::default();
 
-        // 3. 如果列表不为空，默认选中第一项
-        if !tasks.is_empty() {
-            list_state.select(Some(0));
+        // 3. 如果列表不为空，默认选中第一项
+        if !tasks.is_empty() {
+            list_state.select(Some(0));
+        }
+
+        let mut app = Self {
+            exit: false,
+            config,
+            show_dashboard: true, // 初始显示 dashboard
+            tasks,
+            view: Vec::new(),
+            show_future: false,
+            sort: SortMode::default(),
+            filter: None,
+            only_mine: false,
+            show_numbers: false,
+            mode: ModeManager::new(),
+            pending: PendingKeys::default(),
+            last_change: None,
+            prompt: TextInput::default(),
+            completion: None,
+            command_history: Vec::new(),
+            history_index: None,
+            write_path: None,
+            search_query: None,
+            search_origin: None,
+            visual_anchor: None,
+            registers,
+            recording: None,
+            sequence_start: 0,
+            last_macro: None,
+            macro_depth: 0,
+            macro_keys: 0,
+            targets: Vec::new(),
+            list_state,
+            scroll_state: ScrollbarState::default(),
+            help: None,
+            details_focused: false,
+            checkbox_cursor: 0,
+            pomodoro: None,
+            pomodoro_minutes: DEFAULT_POMODORO_MINUTES,
+            status_message: config_error,
+            pending_editor: None,
+            editor_draft: None,
+            music_scroll_state: ScrollbarState::default(), // 初始化
+            music_files: Vec::new(),
+            music_list_state: ListState::default(),
+            music_player_state: MusicPlayerState::default(), // 初始化
+            active_window: None,
+            sink: None,
+            stream_handle: None,
+        };
+        // 2. 在这里调用加载目录的代码
+        // 建议：由于 "F:\\..." 是 Windows 路径，确保你的开发环境路径正确
+        app.load_music_from_dir("F:\\D\\音乐\\音乐文件");
+        app.refresh_view();
+
+        // 3. 返回配置好的 app
+        app
+    }
+}
+
+impl App {
+    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        while !self.exit {
+            terminal.draw(|frame| ui::render(self, frame))?;
+            self.handle_events()?;
+            self.tick();
+
+            // 无名寄存器的内容同步到系统剪贴板
+            if let Some(text) = self.registers.take_clipboard()
+                && let Err(e) = clipboard::copy(&text)
+            {
+                self.status_message = Some(StatusMessage::error(format!("写入剪贴板失败: {e}")));
+            }
+
+            // 编辑器需要接管终端，所以放在事件处理之后执行
+            if let Some(target) = self.pending_editor.take() {
+                self.run_editor(terminal, target)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 暂停界面并用外部编辑器编辑，退出后把结果写回
+    fn run_editor(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        target: EditorTarget,
+    ) -> io::Result<()> {
+        let initial = match target {
+            EditorTarget::Description(i) => match self.tasks.get(i) {
+                Some(task) => task.description.clone(),
+                None => return Ok(()),
+            },
+            EditorTarget::Task(i) => match self.editor_draft.take() {
+                // 上次解析失败的内容优先，避免丢失修改
+                Some((draft_index, draft)) if draft_index == i => draft,
+                _ => match self.tasks.get(i) {
+                    Some(task) => task.to_front_matter(&self.config.fields),
+                    None => return Ok(()),
+                },
+            },
+            EditorTarget::CreateTaskDescription => match &self.active_window {
+                Some(ActiveWindow {
+                    data: WindowData::CreateTask { description, .. },
+                    ..
+                }) => description.text().to_string(),
+                _ => return Ok(()),
+            },
+        };
+
+        let edited = match editor::edit(terminal, &initial) {
+            Ok(edited) => edited,
+            Err(e) => {
+                self.status_message = Some(StatusMessage::error(e.to_string()));
+                return Ok(());
+            }
+        };
+        if edited == initial {
+            return Ok(());
+        }
+
+        match target {
+            EditorTarget::Description(i) => {
+                if let Some(task) = self.tasks.get_mut(i) {
+                    task.description = edited.trim_end().to_string();
+                    self.status_message = Some(StatusMessage::info("描述已更新"));
+                }
+            }
+            EditorTarget::Task(i) => {
+                if let Some(task) = self.tasks.get_mut(i) {
+                    match task.apply_front_matter(&edited, &self.config.fields) {
+                        Ok(()) => {
+                            self.status_message = Some(StatusMessage::info("任务已更新"));
+                            self.refresh_view();
+                        }
+                        Err(e) => {
+                            self.status_message =
+                                Some(StatusMessage::error(format!("解析失败 {e}，按 E 继续编辑")));
+                            self.editor_draft = Some((i, edited));
+                        }
+                    }
+                }
+            }
+            EditorTarget::CreateTaskDescription => {
+                if let Some(ActiveWindow {
+                    data:
+                        WindowData::CreateTask {
+                            description,
+                            current_field,
+                            ..
+                        },
+                    ..
+                }) = &mut self.active_window
+                {
+                    description.set_text(edited.trim_end());
+                    *current_field = 1;
+                }
+            }
+        }
+        self.checkbox_cursor = 0;
+        Ok(())
+    }
+
+    /// 每轮循环调用一次，处理与时间相关的状态
+    fn tick(&mut self) {
+        if self.pomodoro.as_ref().is_some_and(|p| p.is_finished()) {
+            self.finish_pomodoro();
+        }
+        // 计划开始时间到了的任务自动出现在列表中
+        self.refresh_view();
+    }
+
+    fn handle_events(&mut self) -> io::Result<()> {
+        // 带超时地等待事件，让番茄钟倒计时能够刷新
+        if !event::poll(std::time::Duration::from_millis(250))? {
+            return Ok(());
+        }
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            self.handle_key_event(key);
+        }
+        Ok(())
+    }
+
+    /// 处理用户的按键，录制宏时记下按键（开始和结束录制的按键除外）
+    fn handle_key_event(&mut self, key: event::KeyEvent) {
+        let recording = self.recording.is_some();
+        if self.pending.is_empty()
+            && let Some((_, keys)) = &self.recording
+        {
+            self.sequence_start = keys.len();
+        }
+        self.dispatch_key(key);
+        if recording && let Some((_, keys)) = &mut self.recording {
+            keys.push(KeyPress::from(key));
+        }
+    }
+
+    /// 所有按键的统一入口，执行宏时按键也从这里经过
+    fn dispatch_key(&mut self, key: KeyEvent) {
+        // 任意按键都会清除上一条提示
+        self.status_message = None;
+
+        // 如果正在显示 dashboard，按任意键关闭
+        if self.show_dashboard {
+            self.show_dashboard = false;
+            return;
+        }
+
+        // 帮助浮层打开时接收所有按键
+        if self.help.is_some() {
+            self.handle_help_key(key);
+            return;
+        }
+        // F1 在任何地方打开帮助，没有输入框的窗口中也可以按 ?
+        let editing = self.mode.current().can_insert();
+        if key.code == KeyCode::F(1)
+            || (key.code == KeyCode::Char('?') && self.active_window.is_some() && !editing)
+        {
+            self.open_help();
+            return;
+        }
+
+        // 按当前模式分发按键
+        self.handle_mode_aware_event(ModeAwareEvent::Key(key));
+        self.sync_insert_mode();
+    }
+
+    /// 打开帮助浮层，列出当前模式和窗口的按键
+    fn open_help(&mut self) {
+        self.pending.clear();
+        self.help = Some(HelpState::default());
+    }
+
+    /// 帮助浮层的内容：打开的窗口或详情面板、当前模式和帮助自身的按键，都从按键表生成
+    pub fn help_sections(&self) -> Vec<HelpSection> {
+        let mode = self.mode.current();
+        let mut sections = Vec::new();
+        if let Some(window) = &self.active_window {
+            sections.push(HelpSection::new(
+                format!("窗口：{}", window.title()),
+                window.key_hints(),
+            ));
+        } else if self.details_focused {
+            sections.push(HelpSection::new("详情面板", DETAILS_KEYS));
+        }
+        let title = format!("{} 模式", mode.name());
+        let keymap = self.config.keymaps.get(mode);
+        if mode.can_insert() {
+            sections.push(HelpSection::new(title, keymap.help_entries()));
+        } else if self.active_window.is_none() {
+            // 窗口打开时列表的快捷键不起作用，不再列出；动作之后列出计数和寄存器前缀
+            sections.push(HelpSection::new(
+                title,
+                keymap
+                    .help_entries()
+                    .into_iter()
+                    .chain(PREFIX_KEYS.map(|(keys, description)| (keys.to_string(), description))),
+            ));
+        }
+        if mode.can_insert() {
+            sections.push(HelpSection::new("输入框", TEXT_INPUT_KEYS));
+        }
+        if mode == AppMode::Normal && self.active_window.is_none() {
+            sections.push(HelpSection::new(". 能重复的修改", REPEAT_KEYS));
+        }
+        sections.push(HelpSection::new("帮助", HELP_KEYS));
+        sections
+    }
+
+    /// 帮助浮层的按键：滚动、搜索和关闭
+    fn handle_help_key(&mut self, key: KeyEvent) {
+        let Some(help) = &mut self.help else {
+            return;
+        };
+        if help.searching {
+            match key.code {
+                KeyCode::Enter => help.searching = false,
+                KeyCode::Esc => {
+                    help.searching = false;
+                    help.query.clear();
+                }
+                _ => {
+                    edit_input(&mut help.query, &mut self.registers, key);
+                    help.scroll = 0;
+                }
+            }
+            return;
+        }
+
+        let page = half_page() as isize;
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('d') if ctrl => help.scroll_by(page),
+            KeyCode::Char('u') if ctrl => help.scroll_by(-page),
+            KeyCode::Char('j') | KeyCode::Down => help.scroll_by(1),
+            KeyCode::Char('k') | KeyCode::Up => help.scroll_by(-1),
+            KeyCode::PageDown => help.scroll_by(page),
+            KeyCode::PageUp => help.scroll_by(-page),
+            KeyCode::Char('g') | KeyCode::Home => help.scroll = 0,
+            KeyCode::Char('G') | KeyCode::End => help.scroll_to_bottom(),
+            KeyCode::Char('/') => help.searching = true,
+            // 有搜索词时 Esc 先清除搜索
+            KeyCode::Esc if !help.query.is_empty() => {
+                help.query.clear();
+                help.scroll = 0;
+            }
+            KeyCode::Esc | KeyCode::Char('q' | '?') | KeyCode::F(1) => self.help = None,
+            _ => {}
+        }
+    }
+
+    /// 打开或关闭文本输入窗口后进入或离开插入模式
+    fn sync_insert_mode(&mut self) {
+        let editing = self
+            .active_window
+            .as_ref()
+            .is_some_and(ActiveWindow::accepts_text);
+        if editing && !self.mode.is(AppMode::Insert) {
+            self.mode.push_mode(AppMode::Insert);
+        } else if !editing && self.mode.is(AppMode::Insert) {
+            self.mode.pop_mode();
+        }
+    }
+
+    /// 进入新模式，退出时用 `pop_mode` 回到之前的模式
+    fn enter_mode(&mut self, mode: AppMode) {
+        if self.mode.is(mode) {
+            return;
+        }
+        if mode == AppMode::Visual && self.selected_index().is_none() {
+            return;
+        }
+        if matches!(mode, AppMode::Command | AppMode::Search) {
+            self.prompt.clear();
+            self.completion = None;
+            self.history_index = None;
+        }
+        // 记住任务而不是行号，列表重新排序后仍然指向同一个任务
+        if mode == AppMode::Search {
+            self.search_origin = self.selected_task().map(|task| task.id);
+        }
+        if mode == AppMode::Visual {
+            self.visual_anchor = self.selected_task().map(|task| task.id);
+        }
+        self.details_focused = false;
+        self.mode.push_mode(mode);
+    }
+
+    /// 离开可视模式并清除选择
+    fn exit_visual(&mut self) {
+        self.visual_anchor = None;
+        if self.mode.is(AppMode::Visual) {
+            self.mode.pop_mode();
+        }
+    }
+
+    /// 可视模式下选中的行范围，每次按锚点任务当前所在的行计算；锚点任务不在列表中时只选中光标所在行
+    pub fn visual_rows(&self) -> Option<Range<usize>> {
+        let anchor = self.visual_anchor?;
+        let cursor = self.list_state.selected()?;
+        let anchor = self.row_of_task(anchor).unwrap_or(cursor);
+        Some(anchor.min(cursor)..anchor.max(cursor) + 1)
+    }
+
+    /// 任务在列表中所在的行
+    fn row_of_task(&self, id: u64) -> Option<usize> {
+        self.view.iter().position(|&i| self.tasks[i].id == id)
+    }
+
+    /// 批量操作的目标任务 ID：可视模式下为选中的行，否则为当前行
+    fn selection_ids(&self) -> Vec<u64> {
+        let rows = self
+            .visual_rows()
+            .or_else(|| self.list_state.selected().map(|row| row..row + 1))
+            .unwrap_or_default();
+        self.view[rows.start.min(self.view.len())..rows.end.min(self.view.len())]
+            .iter()
+            .map(|&i| self.tasks[i].id)
+            .collect()
+    }
+
+    /// 等待输入的批量操作所针对的任务数
+    pub fn target_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// 取出批量操作的目标任务下标，已被删除的任务会被跳过
+    fn take_targets(&mut self) -> Vec<usize> {
+        std::mem::take(&mut self.targets)
+            .into_iter()
+            .filter_map(|id| self.tasks.iter().position(|task| task.id == id))
+            .collect()
+    }
+
+    /// 普通模式：窗口优先，其次是详情面板，最后是按键映射
+    fn handle_normal_key(&mut self, key: KeyEvent) -> bool {
+        if self.handle_active_window_key(key) {
+            return true;
+        }
+        // 窗口打开时不响应列表的快捷键
+        if self.active_window.is_some() {
+            return false;
+        }
+
+        // 详情面板获得焦点时优先处理
+        if self.details_focused && self.handle_details_key_event(key) {
+            return true;
+        }
+
+        self.handle_mapped_key(key)
+    }
+
+    /// 按当前模式的按键映射处理按键，支持计数和多键序列，返回按键是否被消费
+    fn handle_mapped_key(&mut self, key: KeyEvent) -> bool {
+        let keymap = self.config.keymaps.get(self.mode.current());
+        match self.pending.feed(key, keymap, self.recording.is_some()) {
+            KeyOutcome::Pending | KeyOutcome::Cancelled => true,
+            KeyOutcome::Unmatched => false,
+            KeyOutcome::Action(action, count, register) => {
+                if self.mode.is(AppMode::Visual) {
+                    self.run_visual_action(action, count, register);
+                } else {
+                    self.run_action(action, count, register);
+                }
+                true
+            }
+        }
+    }
+
+    /// 移动到计数指定的行：`gg` 默认第一行，`G` 默认最后一行
+    fn goto_line(&mut self, action: Action, count: Option<usize>) {
+        let last = self.view.len().saturating_sub(1);
+        let row = match (action, count) {
+            (_, Some(line)) => line.saturating_sub(1).min(last),
+            (Action::GotoBottom, None) => last,
+            _ => 0,
+        };
+        self.select_row(row);
+    }
+
+    /// 执行普通模式的动作，`count` 为动作前输入的计数，`register` 为 `"x` 指定的寄存器
+    fn run_action(&mut self, action: Action, count: Option<usize>, register: Option<char>) {
+        let row = self.list_state.selected().unwrap_or(0);
+        let last = self.view.len().saturating_sub(1);
+        let times = count.unwrap_or(1);
+        let has_selection = self.selected_index().is_some();
+        match action {
+            Action::Quit => self.exit = true,
+            Action::Help => self.open_help(),
+            Action::ToggleFocus => {
+                // 在任务列表和详情面板之间切换焦点
+                self.details_focused = !self.details_focused;
+                self.checkbox_cursor = 0;
+            }
+            // 单独的 j/k 在列表两端循环，带计数时停在两端
+            Action::Down | Action::Up | Action::GotoTop | Action::GotoBottom
+                if self.view.is_empty() => {}
+            Action::Down => match count {
+                Some(count) => self.select_row(row.saturating_add(count).min(last)),
+                None => self.next(),
+            },
+            Action::Up => match count {
+                Some(count) => self.select_row(row.saturating_sub(count)),
+                None => self.previous(),
+            },
+            Action::GotoTop | Action::GotoBottom => self.goto_line(action, count),
+            Action::HalfPageDown if !self.view.is_empty() => self.select_row(
+                row.saturating_add(half_page().saturating_mul(times))
+                    .min(last),
+            ),
+            Action::HalfPageUp if !self.view.is_empty() => {
+                self.select_row(row.saturating_sub(half_page().saturating_mul(times)))
+            }
+            Action::HalfPageDown | Action::HalfPageUp => {}
+            // 切换模式
+            Action::VisualMode => {
+                self.handle_mode_aware_event(ModeAwareEvent::ModeChange(AppMode::Visual));
+            }
+            Action::CommandMode => {
+                self.handle_mode_aware_event(ModeAwareEvent::ModeChange(AppMode::Command));
+            }
+            Action::SearchMode => {
+                self.handle_mode_aware_event(ModeAwareEvent::ModeChange(AppMode::Search));
+            }
+            Action::SearchNext => self.search_next(true),
+            Action::SearchPrev => self.search_next(false),
+            // 打开不同窗口
+            Action::NewTask => self.open_window(WindowType::CreateTask),
+            Action::FromTemplate => self.open_templates(),
+            Action::Pomodoro => self.open_window(WindowType::PomodoroSettings),
+            Action::Settings => self.open_window(WindowType::Settings),
+            // 用外部编辑器编辑选中的任务
+            Action::EditDescription => {
+                self.pending_editor = self.selected_index().map(EditorTarget::Description);
+            }
+            Action::EditTask => {
+                self.pending_editor = self.selected_index().map(EditorTarget::Task);
+            }
+            // 计时与预估
+            Action::ToggleTracking => self.toggle_tracking(),
+            Action::Estimate => {
+                if let Some(task) = self.selected_task() {
+                    let initial = task.estimate.map(|e| e.label()).unwrap_or_default();
+                    self.open_input(InputKind::Estimate, initial);
+                }
+            }
+            Action::FinishPomodoro if self.pomodoro.is_some() => self.finish_pomodoro(),
+            Action::FinishPomodoro => {}
+            // 推迟开始日期
+            Action::Defer if has_selection => self.open_window(WindowType::Defer),
+            // 手动调整顺序，移动的行数不超过列表长度
+            Action::MoveUp => {
+                (0..times.min(self.view.len())).for_each(|_| self.move_selected(Movement::Up))
+            }
+            Action::MoveDown => {
+                (0..times.min(self.view.len())).for_each(|_| self.move_selected(Movement::Down))
+            }
+            Action::MoveTop => self.move_selected(Movement::Top),
+            Action::MoveBottom => self.move_selected(Movement::Bottom),
+            // 复制任务与批量调整日期
+            Action::Duplicate => {
+                (0..times.min(CREATE_LIMIT)).for_each(|_| self.duplicate_selected())
+            }
+            // 普通模式调整当前列表中的所有任务
+            Action::BulkDates if !self.view.is_empty() => {
+                self.targets = self.view.iter().map(|&i| self.tasks[i].id).collect();
+                self.open_window(WindowType::BulkDates);
+            }
+            // 链接与附件
+            Action::AddLink if has_selection => self.open_input(InputKind::Link, String::new()),
+            Action::OpenLinks => match self.selected_task().map(|task| task.links.len()) {
+                Some(0) => {
+                    self.status_message = Some(StatusMessage::info("没有链接，按 L 添加"));
+                }
+                Some(_) => self.open_window(WindowType::Links),
+                None => {}
+            },
+            Action::ToggleFuture => {
+                self.show_future = !self.show_future;
+                self.refresh_view();
+                let message = if self.show_future {
+                    "显示全部任务（包括尚未开始的）"
+                } else {
+                    "隐藏尚未开始的任务"
+                };
+                self.status_message = Some(StatusMessage::info(message));
+            }
+            Action::Progress if has_selection => {
+                let current = self
+                    .selected_task()
+                    .and_then(|task| task.manual_progress)
+                    .map(|progress| progress.to_string())
+                    .unwrap_or_default();
+                self.open_input(InputKind::Progress, current);
+            }
+            Action::CyclePriority => {
+                if let Some(task) = self.selected_task_mut() {
+                    // 四种优先级循环，只需切换计数除以 4 的余数次
+                    for _ in 0..times % 4 {
+                        task.priority = Priority::cycle(task.priority);
+                    }
+                    let label = task.priority.map_or("无", |p| p.label());
+                    self.status_message = Some(StatusMessage::info(format!("优先级: {label}")));
+                }
+                self.refresh_view();
+            }
+            // 排序与筛选
+            Action::CycleSort => {
+                self.sort = self.sort.next(&self.config.fields);
+                self.refresh_view();
+                self.status_message = Some(StatusMessage::info(format!(
+                    "按{}排序",
+                    self.sort.label(&self.config.fields)
+                )));
+            }
+            // 负责人
+            Action::Assign if has_selection => {
+                if self.config.roster.is_empty() {
+                    self.status_message = Some(StatusMessage::info("配置中没有 roster 成员"));
+                } else {
+                    self.open_window(WindowType::Assignee);
+                }
+            }
+            Action::ToggleMine => self.toggle_only_mine(),
+            // 收件箱
+            Action::Capture => self.open_input(InputKind::Capture, String::new()),
+            Action::ProcessInbox => self.process_inbox(),
+            Action::Filter => {
+                if self.config.fields.is_empty() {
+                    self.status_message =
+                        Some(StatusMessage::info("配置中没有自定义字段，无法筛选"));
+                } else {
+                    let current = self
+                        .filter
+                        .as_ref()
+                        .map(|f| f.to_string())
+                        .unwrap_or_default();
+                    self.open_input(InputKind::Filter, current);
+                }
+            }
+            // 删除、复制与粘贴
+            Action::Delete if has_selection => {
+                let ids = self.row_ids(row, times);
+                self.yank(&ids, register);
+                self.targets = ids;
+                self.delete_targets();
+            }
+            Action::Yank if has_selection => {
+                let ids = self.row_ids(row, times);
+                self.yank(&ids, register);
+            }
+            Action::Put => self.put(times, register),
+            Action::RecordMacro => self.toggle_recording(register),
+            Action::ReplayMacro => self.replay_macro(register, times),
+            Action::Repeat => {
+                let Some(change) = self.last_change.clone() else {
+                    self.status_message = Some(StatusMessage::info("还没有可以重复的修改"));
+                    return;
+                };
+                self.repeat_change(change, count, register);
+                return;
+            }
+            // 没有选中任务时不可用的动作，以及只在可视模式中使用的动作
+            Action::Defer
+            | Action::BulkDates
+            | Action::AddLink
+            | Action::Progress
+            | Action::Assign
+            | Action::Delete
+            | Action::Yank
+            | Action::Complete
+            | Action::Tag
+            | Action::SetDue
+            | Action::MoveToList
+            | Action::ExitVisual
+            | Action::Submit
+            | Action::Cancel
+            | Action::CompleteNext
+            | Action::CompletePrev
+            | Action::HistoryPrev
+            | Action::HistoryNext => {}
+        }
+        if action.is_change() {
+            self.last_change = Some(Change::Action(action, count, register));
+        }
+    }
+
+    /// 用 `.` 重复修改，新的计数和寄存器替换原来的；批量修改从当前行开始作用于同样多的任务
+    fn repeat_change(&mut self, change: Change, count: Option<usize>, register: Option<char>) {
+        let row = self.list_state.selected().unwrap_or(0);
+        match change {
+            Change::Action(action, last_count, last_register) => {
+                self.run_action(action, count.or(last_count), register.or(last_register));
+            }
+            Change::Complete(rows) => {
+                self.targets = self.row_ids(row, count.unwrap_or(rows));
+                self.complete_targets();
+            }
+            Change::Input(kind, value, rows) => {
+                self.targets = self.row_ids(row, count.unwrap_or(rows));
+                self.submit_input(kind, &value);
+                self.targets.clear();
+            }
+            Change::Defer(preset) => self.defer_selected(preset),
+        }
+    }
+
+    /// 从 `row` 开始的 `count` 行对应的任务 ID
+    fn row_ids(&self, row: usize, count: usize) -> Vec<u64> {
+        let end = row.saturating_add(count).min(self.view.len());
+        self.view[row.min(end)..end]
+            .iter()
+            .map(|&i| self.tasks[i].id)
+            .collect()
+    }
+
+    /// 把任务复制到寄存器，之后可以用 p 粘贴
+    fn yank(&mut self, ids: &[u64], register: Option<char>) {
+        let tasks: Vec<TodoTask> = ids
+            .iter()
+            .filter_map(|&id| self.tasks.iter().find(|task| task.id == id).cloned())
+            .collect();
+        let count = tasks.len();
+        let register = register.unwrap_or(UNNAMED_REGISTER);
+        self.registers
+            .store(register, RegisterContent::Tasks(tasks));
+        let target = if register == UNNAMED_REGISTER {
+            String::new()
+        } else {
+            format!("到寄存器 {}", register.to_ascii_lowercase())
+        };
+        self.status_message = Some(StatusMessage::info(format!(
+            "已复制 {count} 个任务{target}"
+        )));
+    }
+
+    /// 把寄存器中的内容粘贴 `count` 次：任务每次都创建新的副本，文本每行创建一个任务
+    fn put(&mut self, count: usize, register: Option<char>) {
+        let register = register.unwrap_or(UNNAMED_REGISTER);
+        let tasks = match self.registers.get(register) {
+            Some(RegisterContent::Tasks(tasks)) => tasks.clone(),
+            Some(RegisterContent::Text(text)) => text
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(|line| TodoTask::new(line.to_string(), String::new()))
+                .collect(),
+            None if register == UNNAMED_REGISTER => {
+                self.status_message = Some(StatusMessage::info("没有复制的任务，先用 yy 复制"));
+                return;
+            }
+            None => {
+                self.status_message =
+                    Some(StatusMessage::info(format!("寄存器 {register} 是空的")));
+                return;
+            }
+        };
+        let count = count.min(CREATE_LIMIT);
+        let mut first = None;
+        for _ in 0..count {
+            for task in &tasks {
+                let mut copy = task.duplicate();
+                copy.id = self.next_task_id();
+                copy.position = self.next_position();
+                copy.created_by = self.config.user.clone();
+                first.get_or_insert(copy.id);
+                self.tasks.push(copy);
+            }
+        }
+        self.refresh_view();
+        if let Some(id) = first {
+            self.jump_to_task(id);
+        }
+        self.status_message = Some(StatusMessage::info(format!(
+            "已粘贴 {} 个任务",
+            count * tasks.len()
+        )));
+    }
+
+    /// 开始录制宏到 `register`，正在录制时结束录制
+    fn toggle_recording(&mut self, register: Option<char>) {
+        if let Some((name, mut keys)) = self.recording.take() {
+            // 去掉结束录制的按键序列中已经录下的前缀，例如 gq 的 g
+            keys.truncate(self.sequence_start);
+            let keys = format_keys(&keys);
+            self.status_message = Some(StatusMessage::info(format!(
+                "已录制宏 @{}: {keys}，写入配置的 [macros] 可以在下次启动时使用",
+                name.to_ascii_lowercase()
+            )));
+            self.registers.store_macro(name, keys);
+            return;
+        }
+        match register.filter(char::is_ascii_alphabetic) {
+            Some(name) => {
+                self.recording = Some((name, Vec::new()));
+                let stop = self
+                    .config
+                    .keymaps
+                    .normal
+                    .key_for(Action::RecordMacro)
+                    .unwrap_or_default();
+                self.status_message = Some(StatusMessage::info(format!(
+                    "开始录制宏 @{}，按 {stop} 结束",
+                    name.to_ascii_lowercase()
+                )));
+            }
+            None => {
+                self.status_message = Some(StatusMessage::error("宏只能录制到 a–z 寄存器"));
+            }
+        }
+    }
+
+    /// 正在录制的宏的寄存器
+    pub fn recording_register(&self) -> Option<char> {
+        self.recording
+            .as_ref()
+            .map(|(name, _)| name.to_ascii_lowercase())
+    }
+
+    /// 把寄存器中的宏执行 `count` 次，`@` 表示上次执行的宏；出错时停止执行
+    fn replay_macro(&mut self, register: Option<char>, count: usize) {
+        let name = match register {
+            Some('@') => match self.last_macro {
+                Some(name) => name,
+                None => {
+                    self.status_message = Some(StatusMessage::info("还没有执行过宏"));
+                    return;
+                }
+            },
+            Some(name) if name.is_ascii_alphabetic() => name.to_ascii_lowercase(),
+            _ => {
+                self.status_message = Some(StatusMessage::error("宏只能保存在 a–z 寄存器"));
+                return;
+            }
+        };
+        let keys = match self.registers.get(name) {
+            Some(RegisterContent::Text(text)) => parse_keys(text),
+            Some(RegisterContent::Tasks(_)) => Err(format!("寄存器 {name} 中是任务，不是宏")),
+            None => Err(format!("寄存器 {name} 是空的")),
+        };
+        let keys = match keys {
+            Ok(keys) => keys,
+            Err(e) => {
+                self.status_message = Some(StatusMessage::error(e));
+                return;
+            }
+        };
+        if self.macro_depth >= MACRO_DEPTH_LIMIT {
+            self.status_message = Some(StatusMessage::error("宏嵌套过深，已停止执行"));
+            return;
+        }
+
+        self.last_macro = Some(name);
+        if self.macro_depth == 0 {
+            self.macro_keys = 0;
+        }
+        self.macro_depth += 1;
+        'replay: for _ in 0..count {
+            for key in &keys {
+                if self.macro_keys >= MACRO_KEY_LIMIT {
+                    self.status_message =
+                        Some(StatusMessage::error("宏执行的按键过多，已停止执行"));
+                    break 'replay;
+                }
+                self.macro_keys += 1;
+                self.dispatch_key(KeyEvent::new(key.code, key.modifiers));
+                let failed = self
+                    .status_message
+                    .as_ref()
+                    .is_some_and(|message| message.is_error);
+                if failed || self.exit {
+                    break 'replay;
+                }
+            }
+        }
+        self.macro_depth -= 1;
+    }
+
+    /// 可视模式：移动光标扩展选择，对选中的任务执行批量操作
+    fn run_visual_action(&mut self, action: Action, count: Option<usize>, register: Option<char>) {
+        let row = self.list_state.selected().unwrap_or(0);
+        let last = self.view.len().saturating_sub(1);
+        let times = count.unwrap_or(1);
+        match action {
+            // 选择不循环，停在列表两端
+            Action::Down => self.select_row(row.saturating_add(times).min(last)),
+            Action::Up => self.select_row(row.saturating_sub(times)),
+            Action::GotoTop | Action::GotoBottom => self.goto_line(action, count),
+            Action::Delete => {
+                let ids = self.selection_ids();
+                self.yank(&ids, register);
+                self.exit_visual();
+                self.confirm(ConfirmAction::DeleteTasks(ids));
+            }
+            Action::Yank => {
+                let ids = self.selection_ids();
+                self.yank(&ids, register);
+                self.exit_visual();
+            }
+            Action::Complete => {
+                self.targets = self.selection_ids();
+                self.exit_visual();
+                self.complete_targets();
+            }
+            // 需要输入的操作先记下目标任务，提交时再应用
+            Action::Tag => {
+                self.targets = self.selection_ids();
+                self.exit_visual();
+                self.open_input(InputKind::Tags, String::new());
+            }
+            Action::SetDue => {
+                self.targets = self.selection_ids();
+                self.exit_visual();
+                self.open_input(InputKind::Due, String::new());
+            }
+            Action::MoveToList => {
+                self.targets = self.selection_ids();
+                self.exit_visual();
+                self.open_window(WindowType::MoveToList);
+            }
+            Action::BulkDates => {
+                self.targets = self.selection_ids();
+                self.exit_visual();
+                self.open_window(WindowType::BulkDates);
+            }
+            // 整块移动选中的任务，锚点和光标记的是任务，选择随任务一起移动
+            Action::MoveUp | Action::MoveDown => {
+                let movement = if action == Action::MoveUp {
+                    Movement::Up
+                } else {
+                    Movement::Down
+                };
+                for _ in 0..times.min(self.view.len()) {
+                    if let Some(rows) = self.visual_rows() {
+                        self.move_rows(rows, movement);
+                    }
+                }
+            }
+            Action::MoveTop | Action::MoveBottom => {
+                if let Some(rows) = self.visual_rows() {
+                    let movement = if action == Action::MoveTop {
+                        Movement::Top
+                    } else {
+                        Movement::Bottom
+                    };
+                    self.move_rows(rows, movement);
+                }
+            }
+            Action::ExitVisual => self.exit_visual(),
+            Action::Help => self.open_help(),
+            // 和 Vim 一样，命令行预先填入选中的行范围
+            Action::CommandMode => {
+                let rows = self.visual_rows().unwrap_or_default();
+                self.exit_visual();
+                self.enter_mode(AppMode::Command);
+                self.prompt
+                    .set_text(format!("{},{}", rows.start + 1, rows.end));
+            }
+            _ => {}
+        }
+    }
+
+    /// 命令模式和搜索模式：编辑底部的输入行，按按键映射提交、取消、补全和浏览历史
+    fn handle_prompt_key(&mut self, key: KeyEvent) -> bool {
+        let command = self.mode.is(AppMode::Command);
+        let action = self
+            .config
+            .keymaps
+            .get(self.mode.current())
+            .action_for(KeyPress::from(key));
+        // 除补全外的按键都会结束补全
+        if !matches!(action, Some(Action::CompleteNext | Action::CompletePrev)) {
+            self.completion = None;
+        }
+        match action {
+            Some(Action::Cancel) => {
+                if !command {
+                    self.restore_search_origin();
+                }
+                self.mode.pop_mode();
+            }
+            Some(Action::Submit) => {
+                let input = self.prompt.take();
+                let event = if command {
+                    self.remember_command(&input);
+                    ModeAwareEvent::Command(input)
+                } else {
+                    ModeAwareEvent::Search(input)
+                };
+                self.mode.pop_mode();
+                return self.handle_mode_aware_event(event);
+            }
+            Some(Action::CompleteNext) if command => self.complete_command(true),
+            Some(Action::CompletePrev) if command => self.complete_command(false),
+            Some(Action::HistoryPrev) if command => self.browse_history(true),
+            Some(Action::HistoryNext) if command => self.browse_history(false),
+            Some(Action::Help) => self.open_help(),
+            // 输入行为空时退格退出
+            _ if key.code == KeyCode::Backspace && self.prompt.is_empty() => {
+                if !command {
+                    self.restore_search_origin();
+                }
+                self.mode.pop_mode();
+            }
+            _ if self.mode.current().can_insert() => {
+                edit_input(&mut self.prompt, &mut self.registers, key);
+                if !command {
+                    self.incremental_search();
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// 把命令加入历史，重复的命令只保留最近一次
+    fn remember_command(&mut self, input: &str) {
+        let input = input.trim();
+        if input.is_empty() {
+            return;
+        }
+        self.command_history.retain(|command| command != input);
+        self.command_history.push(input.to_string());
+        if self.command_history.len() > COMMAND_HISTORY_LIMIT {
+            self.command_history.remove(0);
+        }
+    }
+
+    /// 用上一条（`older`）或下一条历史命令替换输入行，越过最新一条时清空
+    fn browse_history(&mut self, older: bool) {
+        let len = self.command_history.len();
+        self.history_index = match (self.history_index, older) {
+            (None, true) => len.checked_sub(1),
+            (Some(i), true) => Some(i.saturating_sub(1)),
+            (Some(i), false) if i + 1 < len => Some(i + 1),
+            (_, false) => None,
+        };
+        self.prompt.set_text(
+            self.history_index
+                .map(|i| self.command_history[i].clone())
+                .unwrap_or_default(),
+        );
+    }
+
+    /// Tab 补全命令行末尾的单词：唯一候选直接填入，多个候选先填入公共前缀，
+    /// 之后每次 Tab（`forward`）或 Shift-Tab 在候选项之间循环
+    fn complete_command(&mut self, forward: bool) {
+        if let Some(completion) = &mut self.completion {
+            let len = completion.candidates.len();
+            let next = match (completion.selected, forward) {
+                (None, true) => 0,
+                (None, false) => len - 1,
+                (Some(i), true) => (i + 1) % len,
+                (Some(i), false) => (i + len - 1) % len,
+            };
+            completion.selected = Some(next);
+            let line = format!(
+                "{}{}",
+                &self.prompt.text()[..completion.start],
+                completion.candidates[next]
+            );
+            self.prompt.set_text(line);
+            return;
+        }
+
+        let mut tags: Vec<String> = self
+            .tasks
+            .iter()
+            .flat_map(|task| task.tags.iter().map(|tag| tag.name().to_string()))
+            .collect();
+        tags.sort_unstable();
+        tags.dedup();
+        let (start, candidates) = complete_line(self.prompt.text(), &self.config.fields, &tags);
+        let replacement = match candidates.len() {
+            0 => return,
+            1 => candidates[0].clone(),
+            _ => common_prefix(&candidates).to_string(),
+        };
+        let line = format!("{}{replacement}", &self.prompt.text()[..start]);
+        self.prompt.set_text(line);
+        if candidates.len() > 1 {
+            self.completion = Some(Completion {
+                start,
+                candidates,
+                selected: None,
+            });
+        }
+    }
+
+    /// 执行命令行中的命令，出错时在状态栏显示原因
+    fn execute_command(&mut self, input: &str) -> bool {
+        if input.trim().is_empty() {
+            return true;
+        }
+        match ExCommand::parse(input).and_then(|command| self.run_command(command)) {
+            Ok(()) => true,
+            Err(e) => {
+                self.status_message = Some(StatusMessage::error(e));
+                false
+            }
+        }
+    }
+
+    fn run_command(&mut self, command: ExCommand) -> Result<(), String> {
+        let args = command.args.as_str();
+        match command.kind {
+            CommandKind::Add if args.is_empty() => return Err("用法 :add 任务标题 @情境".into()),
+            CommandKind::Add => self.submit_input(InputKind::Capture, args),
+            CommandKind::Done => {
+                self.targets = self.range_ids(command.range)?;
+                self.complete_targets();
+            }
+            CommandKind::Delete => {
+                let ids = self.range_ids(command.range)?;
+                self.confirm(ConfirmAction::DeleteTasks(ids));
+            }
+            CommandKind::Tag if args.is_empty() => return Err("用法 :tag +标签 -标签".into()),
+            // 标签和截止日期沿用批量输入窗口的处理，参数为空时清除截止日期
+            CommandKind::Tag | CommandKind::Due => {
+                self.targets = self.range_ids(command.range)?;
+                let kind = if command.kind == CommandKind::Tag {
+                    InputKind::Tags
+                } else {
+                    InputKind::Due
+                };
+                self.submit_input(kind, args);
+                self.targets.clear();
+            }
+            CommandKind::Sort => {
+                self.sort = if args.is_empty() {
+                    self.sort.next(&self.config.fields)
+                } else {
+                    SortMode::parse(args, &self.config.fields)
+                        .ok_or_else(|| format!("未知的排序方式 `{args}`"))?
+                };
+                self.refresh_view();
+                self.status_message = Some(StatusMessage::info(format!(
+                    "按{}排序",
+                    self.sort.label(&self.config.fields)
+                )));
+            }
+            CommandKind::Filter => {
+                self.filter = if args.is_empty() {
+                    None
+                } else {
+                    Some(FieldFilter::parse(args, &self.config.fields)?)
+                };
+                self.refresh_view();
+                let message = if args.is_empty() {
+                    "已清除筛选".to_string()
+                } else {
+                    format!("筛选 {args}：{} 个任务", self.view.len())
+                };
+                self.status_message = Some(StatusMessage::info(message));
+            }
+            CommandKind::Write => self.write_tasks(args)?,
+            CommandKind::Quit => self.exit = true,
+            CommandKind::WriteQuit => {
+                self.write_tasks(args)?;
+                self.exit = true;
+            }
+            CommandKind::Set => self.set_options(args)?,
+            CommandKind::NoHighlight => self.search_query = None,
+        }
+        Ok(())
+    }
+
+    /// 行范围对应的任务 ID，没有范围时为当前行
+    fn range_ids(&self, range: Option<LineRange>) -> Result<Vec<u64>, String> {
+        let ids = match range {
+            Some(range) => {
+                let rows = range.resolve(self.list_state.selected(), self.view.len())?;
+                self.view[rows].iter().map(|&i| self.tasks[i].id).collect()
+            }
+            None => self.selection_ids(),
+        };
+        if ids.is_empty() {
+            return Err("没有可操作的任务".into());
         }
+        Ok(ids)
+    }
 
-        let tasks_len = tasks.len();
-        let mut app = Self {
-            exit: false,
-            show_dashboard: true, // 初始显示 dashboard
-            tasks,
-            list_state,
-            scroll_state: ScrollbarState::new(tasks_len),
-            music_scroll_state: ScrollbarState::default(), // 初始化
-            music_files: Vec::new(),
-            music_list_state: ListState::default(),
-            music_player_state: MusicPlayerState::default(), // 初始化
-            active_window: None,
-            sink: None,
-            stream_handle: None,
+    /// 把所有任务写入 Markdown 文件，每个任务一段 front matter 文档
+    ///
+    /// 不给文件名时写入上次的文件
+    fn write_tasks(&mut self, path: &str) -> Result<(), String> {
+        let path = if path.is_empty() {
+            self.write_path
+                .clone()
+                .ok_or("没有文件名，用法 :w 文件名")?
+        } else {
+            PathBuf::from(path)
         };
-        // 2. 在这里调用加载目录的代码
-        // 建议：由于 "F:\\..." 是 Windows 路径，确保你的开发环境路径正确
-        app.load_music_from_dir("F:\\D\\音乐\\音乐文件");
+        let document = self
+            .tasks
+            .iter()
+            .map(|task| task.to_export(&self.config.fields))
+            .collect::<Vec<_>>()
+            .join("\n");
+        std::fs::write(&path, document).map_err(|e| format!("无法写入 {}: {e}", path.display()))?;
+        self.status_message = Some(StatusMessage::info(format!(
+            "已写入 {} 个任务到 {}",
+            self.tasks.len(),
+            path.display()
+        )));
+        self.write_path = Some(path);
+        Ok(())
+    }
 
-        // 3. 返回配置好的 app
-        app
+    /// 处理 `:set`，多个选项用空格分隔，不带参数时显示所有选项
+    fn set_options(&mut self, args: &str) -> Result<(), String> {
+        for arg in args.split_whitespace() {
+            self.set_option(arg)?;
+        }
+        let flag = |on: bool, name: &str| {
+            if on {
+                name.to_string()
+            } else {
+                format!("no{name}")
+            }
+        };
+        self.status_message = Some(StatusMessage::info(format!(
+            "{} {} {} pomodoro={}",
+            flag(self.show_numbers, "number"),
+            flag(self.show_future, "future"),
+            flag(self.only_mine, "mine"),
+            self.pomodoro_minutes
+        )));
+        Ok(())
     }
-}
 
-impl App {
-    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
-        while !self.exit {
-            terminal.draw(|frame| ui::render(self, frame))?;
-            self.handle_events()?;
+    /// 设置单个选项：`选项`、`no选项`、`选项!`（切换）或 `选项=值`
+    fn set_option(&mut self, arg: &str) -> Result<(), String> {
+        if let Some((name, value)) = arg.split_once('=') {
+            return match name {
+                "pomodoro" => {
+                    self.pomodoro_minutes = value
+                        .parse()
+                        .ok()
+                        .filter(|minutes| POMODORO_MINUTES_RANGE.contains(minutes))
+                        .ok_or_else(|| {
+                            format!(
+                                "番茄钟时长应为 {}–{} 分钟，而不是 `{value}`",
+                                POMODORO_MINUTES_RANGE.start(),
+                                POMODORO_MINUTES_RANGE.end()
+                            )
+                        })?;
+                    Ok(())
+                }
+                _ if SET_FLAGS.contains(&name) => Err(format!(
+                    "{name} 是开关选项，用 :set {name} 或 :set no{name}"
+                )),
+                _ => Err(format!("未知选项 {name}")),
+            };
         }
+
+        let (name, on) = if let Some(name) = arg.strip_suffix('!') {
+            (name, None)
+        } else if let Some(name) = arg.strip_prefix("no").filter(|n| SET_FLAGS.contains(n)) {
+            (name, Some(false))
+        } else {
+            (arg, Some(true))
+        };
+        match name {
+            "number" => self.show_numbers = on.unwrap_or(!self.show_numbers),
+            "future" => self.show_future = on.unwrap_or(!self.show_future),
+            "mine" => {
+                let on = on.unwrap_or(!self.only_mine);
+                if on && self.config.user.is_none() {
+                    return Err("配置中没有设置 user，无法筛选我的任务".into());
+                }
+                self.only_mine = on;
+            }
+            "pomodoro" => return Err("用法 :set pomodoro=25".into()),
+            _ => return Err(format!("未知选项 {name}")),
+        }
+        self.refresh_view();
         Ok(())
     }
 
-    fn handle_events(&mut self) -> io::Result<()> {
-        if let Event::Key(key) = event::read()?
-            && key.kind == KeyEventKind::Press
+    /// 当前用于高亮的关键字：搜索时为正在输入的内容，否则为上次搜索的关键字
+    pub fn search_pattern(&self) -> Option<&str> {
+        if self.mode.is(AppMode::Search) {
+            return Some(self.prompt.text()).filter(|input| !input.is_empty());
+        }
+        self.search_query.as_deref()
+    }
+
+    /// 列表中匹配当前关键字的行
+    pub fn search_matches(&self) -> Vec<usize> {
+        let Some(pattern) = self.search_pattern() else {
+            return Vec::new();
+        };
+        (0..self.view.len())
+            .filter(|&row| self.tasks[self.view[row]].matches(pattern))
+            .collect()
+    }
+
+    /// 从 `from` 行开始（包括该行）按方向查找匹配的行，到头后从另一端继续，
+    /// 返回找到的行以及是否绕回
+    fn find_match(&self, from: usize, forward: bool) -> Option<(usize, bool)> {
+        let matches = self.search_matches();
+        let found = if forward {
+            matches
+                .iter()
+                .find(|&&row| row >= from)
+                .map(|&row| (row, false))
+        } else {
+            matches
+                .iter()
+                .rev()
+                .find(|&&row| row <= from)
+                .map(|&row| (row, false))
+        };
+        found.or_else(|| {
+            let wrapped = if forward {
+                matches.first()
+            } else {
+                matches.last()
+            };
+            wrapped.map(|&row| (row, true))
+        })
+    }
+
+    /// 边输入边跳到开始搜索处之后的第一个匹配，没有匹配时回到原处
+    fn incremental_search(&mut self) {
+        let origin = self.search_origin_row();
+        match self.find_match(origin, true) {
+            Some((row, _)) => self.select_row(row),
+            None => self.restore_search_origin(),
+        }
+    }
+
+    /// 开始搜索时选中的任务现在所在的行
+    fn search_origin_row(&self) -> usize {
+        self.search_origin
+            .and_then(|id| self.row_of_task(id))
+            .unwrap_or(0)
+    }
+
+    /// 取消搜索时回到开始搜索时选中的任务
+    fn restore_search_origin(&mut self) {
+        if let Some(row) = self
+            .search_origin
+            .take()
+            .and_then(|id| self.row_of_task(id))
         {
-            self.handle_key_event(key);
+            self.select_row(row);
         }
-        Ok(())
     }
 
-    fn handle_key_event(&mut self, key: event::KeyEvent) {
-        // 如果正在显示 dashboard，按任意键关闭
-        if self.show_dashboard {
-            self.show_dashboard = false;
-            return;
+    /// 提交搜索：输入为空时重复上次搜索，选中增量搜索找到的匹配
+    fn search(&mut self, query: &str) -> bool {
+        let query = query.trim();
+        if !query.is_empty() {
+            self.search_query = Some(query.to_string());
+        }
+        let origin = self.search_origin_row();
+        self.search_origin = None;
+        self.jump_to_match(origin, true)
+    }
+
+    /// n/N：跳到下一个或上一个匹配
+    fn search_next(&mut self, forward: bool) {
+        let row = self.list_state.selected().unwrap_or(0);
+        let from = if forward {
+            row + 1
+        } else {
+            row.checked_sub(1).unwrap_or(self.view.len())
+        };
+        self.jump_to_match(from, forward);
+    }
+
+    /// 选中从 `from` 开始的匹配，在状态栏显示关键字和匹配序号
+    fn jump_to_match(&mut self, from: usize, forward: bool) -> bool {
+        let Some(query) = self.search_query.clone() else {
+            self.status_message = Some(StatusMessage::info("没有搜索过的关键字，按 / 搜索"));
+            return false;
+        };
+        let Some((row, wrapped)) = self.find_match(from, forward) else {
+            self.status_message = Some(StatusMessage::error(format!("未找到 `{query}`")));
+            return false;
+        };
+        self.select_row(row);
+        let matches = self.search_matches();
+        let index = matches.iter().position(|&r| r == row).unwrap_or(0);
+        let prefix = match (wrapped, forward) {
+            (false, _) => "",
+            (true, true) => "已到底部，从头继续 ",
+            (true, false) => "已到顶部，从底部继续 ",
+        };
+        self.status_message = Some(StatusMessage::info(format!(
+            "{prefix}/{query} [{}/{}]",
+            index + 1,
+            matches.len()
+        )));
+        true
+    }
+
+    /// 按插入模式的按键映射改写按键：确认和取消换成窗口认得的 Enter 和 Esc，
+    /// 没有绑定的 Enter 和 Esc 不再起作用；返回 `None` 表示按键已经处理
+    fn translate_insert_key(&mut self, key: KeyEvent) -> Option<KeyEvent> {
+        match self.config.keymaps.insert.action_for(KeyPress::from(key)) {
+            Some(Action::Submit) => Some(KeyEvent::from(KeyCode::Enter)),
+            Some(Action::Cancel) => Some(KeyEvent::from(KeyCode::Esc)),
+            Some(Action::Help) => {
+                self.open_help();
+                None
+            }
+            _ if key.modifiers.is_empty() && matches!(key.code, KeyCode::Enter | KeyCode::Esc) => {
+                None
+            }
+            _ => Some(key),
         }
+    }
+
+    /// 把按键交给活动窗口，返回窗口是否消费了这个按键
+    fn handle_active_window_key(&mut self, key: KeyEvent) -> bool {
         // 1. 暂时取走窗口
         if let Some(mut window) = self.active_window.take() {
             // 2. 处理事件
             let handled = self.handle_window_key_event(&mut window, key);
 
             // 3. 根据窗口类型和按键决定是否关闭
-            let should_close = match key.code {
-                KeyCode::Esc => true, // Esc 总是取消并关闭
-                KeyCode::Enter => {
-                    // 只有特定窗口类型的 Enter 才关闭
-                    match window.window_type {
-                        WindowType::CreateTask => true,        // 创建任务窗口按 Enter 关闭
-                        WindowType::PomodoroSettings => false, // 番茄钟设置窗口不关闭
-                        WindowType::Settings => false,         // 设置窗口不关闭
-                        WindowType::Search => true,            // 搜索窗口按 Enter 关闭
+            let should_close = !window.is_visible
+                || match key.code {
+                    KeyCode::Esc => true, // Esc 总是取消并关闭
+                    KeyCode::Enter => {
+                        // 只有特定窗口类型的 Enter 才关闭
+                        match window.window_type {
+                            // 创建任务窗口创建成功后隐藏，自定义字段有误时保持打开
+                            WindowType::CreateTask => false,
+                            // 番茄钟设置窗口只有开始番茄钟时关闭，在音乐列表中按 Enter 是播放
+                            WindowType::PomodoroSettings => matches!(
+                                window.data,
+                                WindowData::PomodoroSettings { current_focus, .. } if current_focus != 2
+                            ),
+                            WindowType::Settings => false, // 设置窗口不关闭
+                            WindowType::Input => true,     // 输入窗口按 Enter 提交
+                            WindowType::Defer => true,     // 推迟窗口按 Enter 确认
+                            WindowType::Links => true,     // 链接窗口按 Enter 打开
+                            WindowType::Templates => true, // 模板窗口按 Enter 选择
+                            WindowType::BulkDates => true, // 批量日期窗口按 Enter 执行
+                            WindowType::Confirm => true,   // 确认窗口按 Enter 确认
+                            WindowType::Assignee => true,  // 负责人窗口按 Enter 分配
+                            WindowType::Inbox => false,    // 收件箱窗口处理完最后一项时关闭
+                            WindowType::MoveToList => true, // 清单窗口按 Enter 移动
+                        }
                     }
-                }
-                _ => false,
-            };
+                    // 确认窗口按 y / n 后关闭
+                    KeyCode::Char('y' | 'n') => window.window_type == WindowType::Confirm,
+                    _ => false,
+                };
 
-            if !should_close {
-                // 如果不需要关闭，把窗口放回去
-                self.active_window = Some(window);
-            } else {
+            if should_close {
                 // 需要关闭窗口，局部变量 window 会在作用域结束时被销毁
-                return;
+                return true;
             }
-
-            if handled {
-                return;
+            // 如果不需要关闭，把窗口放回去（处理按键时打开了其他窗口的除外）
+            if self.active_window.is_none() {
+                self.active_window = Some(window);
             }
-        }
 
-        // 4. 全局快捷键逻辑 (当没有窗口或窗口未拦截事件时触发)
-        match key.code {
-            KeyCode::Char('q') => self.exit = true,
-            KeyCode::Char('j') | KeyCode::Down => {
-                if self.active_window.is_none() {
-                    self.next();
-                }
-            }
-            KeyCode::Char('k') | KeyCode::Up => {
-                if self.active_window.is_none() {
-                    self.previous();
-                }
-            }
-            // 快捷键打开不同窗口
-            KeyCode::Char('a') => self.open_window(WindowType::CreateTask),
-            KeyCode::Char('p') => self.open_window(WindowType::PomodoroSettings),
-            KeyCode::Char('o') => self.open_window(WindowType::Settings),
-            _ => {}
+            return handled;
         }
+        false
     }
 
     /// 处理窗口内的键盘事件
@@ -265,91 +1815,70 @@ impl App {
             WindowData::CreateTask {
                 title,
                 description,
+                fields,
                 current_field,
-                cursor_position, // 新增
+                template,
             } => match key.code {
                 KeyCode::Tab => {
-                    *current_field = (*current_field + 1) % 2;
-                    // 切换字段时重置光标位置
-                    *cursor_position = if *current_field == 0 {
-                        title.len()
-                    } else {
-                        description.len()
-                    };
-                    true
-                }
-                KeyCode::Enter => {
-                    self.create_task(title.clone(), description.clone());
-                    self.close_window();
+                    *current_field = (*current_field + 1) % (fields.len() + 2);
                     true
                 }
-                KeyCode::Esc => {
-                    self.close_window();
+                KeyCode::BackTab => {
+                    *current_field = (*current_field + fields.len() + 1) % (fields.len() + 2);
                     true
                 }
-                KeyCode::Left => {
-                    if *cursor_position > 0 {
-                        *cursor_position -= 1;
+                // Enter 创建任务，Alt-Enter 在描述中换行
+                KeyCode::Enter if key.modifiers.is_empty() => {
+                    // 自定义字段按类型解析，有误时跳到出错的字段
+                    let mut values = BTreeMap::new();
+                    for (i, (field, input)) in self.config.fields.iter().zip(&*fields).enumerate() {
+                        if input.text().trim().is_empty() {
+                            continue;
+                        }
+                        match field.parse(input.text()) {
+                            Ok(value) => {
+                                values.insert(field.name.clone(), value);
+                            }
+                            Err(e) => {
+                                self.status_message = Some(StatusMessage::error(e));
+                                *current_field = i + 2;
+                                return true;
+                            }
+                        }
                     }
-                    true
-                }
-                KeyCode::Right => {
-                    let max_len = if *current_field == 0 {
-                        title.len()
-                    } else {
-                        description.len()
-                    };
-                    if *cursor_position < max_len {
-                        *cursor_position += 1;
+                    self.create_task(
+                        title.text().to_string(),
+                        description.text().to_string(),
+                        *template,
+                    );
+                    if let Some(task) = self.tasks.last_mut() {
+                        task.fields = values;
                     }
+                    window.is_visible = false;
                     true
                 }
-                KeyCode::Home => {
-                    *cursor_position = 0;
-                    true
-                }
-                KeyCode::End => {
-                    *cursor_position = if *current_field == 0 {
-                        title.len()
-                    } else {
-                        description.len()
-                    };
+                KeyCode::Esc => {
+                    self.close_window();
                     true
                 }
-                KeyCode::Char(c) => {
-                    let text = if *current_field == 0 {
-                        title
-                    } else {
-                        description
-                    };
-                    text.insert(*cursor_position, c);
-                    *cursor_position += 1;
+                KeyCode::Char('o') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    // Ctrl-O 在外部编辑器中编辑描述（Ctrl-E 留给输入框移到行尾）
+                    self.pending_editor = Some(EditorTarget::CreateTaskDescription);
                     true
                 }
-                KeyCode::Backspace => {
-                    if *cursor_position > 0 {
-                        let text = if *current_field == 0 {
-                            title
-                        } else {
-                            description
-                        };
-                        text.remove(*cursor_position - 1);
-                        *cursor_position -= 1;
-                    }
+                KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    // Ctrl-T 从模板新建，会替换已输入的内容
+                    self.open_templates();
                     true
                 }
-                KeyCode::Delete => {
-                    let text = if *current_field == 0 {
-                        title
-                    } else {
-                        description
+                _ => {
+                    let input = match *current_field {
+                        0 => title,
+                        1 => description,
+                        i => &mut fields[i - 2],
                     };
-                    if *cursor_position < text.len() {
-                        text.remove(*cursor_position);
-                    }
-                    true
+                    edit_input(input, &mut self.registers, key)
                 }
-                _ => false,
             },
 
             WindowData::PomodoroSettings {
@@ -369,10 +1898,7 @@ impl App {
                             true
                         } else {
                             // 保存设置
-                            self.save_pomodoro_settings(
-                                *selected_duration,
-                                custom_duration.clone(),
-                            );
+                            self.save_pomodoro_settings(*selected_duration, custom_duration.text());
                             true
                         }
                     }
@@ -380,6 +1906,17 @@ impl App {
                         self.close_window();
                         true
                     }
+                    // 自定义时间只接受数字，其余编辑按键交给输入框
+                    KeyCode::Char(c)
+                        if *current_focus == 1
+                            && key.modifiers.is_empty()
+                            && !c.is_ascii_digit() =>
+                    {
+                        false
+                    }
+                    _ if *current_focus == 1 => {
+                        edit_input(custom_duration, &mut self.registers, key)
+                    }
                     KeyCode::Char(' ') => {
                         if *current_focus == 2 {
                             // 在音乐列表按空格控制播放/暂停
@@ -404,7 +1941,7 @@ impl App {
                     KeyCode::Down | KeyCode::Char('j') => {
                         if *current_focus == 0 {
                             // 常用时间选择
-                            if *selected_duration < 4 {
+                            if *selected_duration < POMODORO_PRESETS.len() - 1 {
                                 *selected_duration += 1;
                             }
                         } else if *current_focus == 2 {
@@ -481,10 +2018,391 @@ impl App {
                     _ => false,
                 }
             }
+            WindowData::Defer { selected } => match key.code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    *selected = selected.saturating_sub(1);
+                    true
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    *selected = (*selected + 1).min(DeferPreset::ALL.len() - 1);
+                    true
+                }
+                KeyCode::Enter => {
+                    self.defer_selected(DeferPreset::ALL[*selected]);
+                    true
+                }
+                _ => true,
+            },
+            WindowData::Confirm { action } => {
+                if matches!(key.code, KeyCode::Enter | KeyCode::Char('y')) {
+                    match action {
+                        ConfirmAction::CompleteTask(id) => self.complete_task(*id),
+                        ConfirmAction::DeleteTasks(ids) => {
+                            self.targets = std::mem::take(ids);
+                            self.delete_targets();
+                        }
+                    }
+                }
+                true
+            }
+            WindowData::MoveToList { selected } => {
+                let lists = self.known_lists();
+                match key.code {
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        *selected = selected.saturating_sub(1);
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        *selected = (*selected + 1).min(lists.len());
+                    }
+                    KeyCode::Enter => self.move_targets_to(lists.get(*selected).cloned()),
+                    _ => {}
+                }
+                true
+            }
+            WindowData::Assignee { selected } => {
+                match key.code {
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        *selected = selected.saturating_sub(1);
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        *selected = (*selected + 1).min(self.config.roster.len());
+                    }
+                    KeyCode::Enter => {
+                        let handle = selected
+                            .checked_sub(1)
+                            .and_then(|i| self.config.roster.get(i))
+                            .map(|member| member.handle.clone());
+                        self.assign_selected(handle);
+                    }
+                    _ => {}
+                }
+                true
+            }
+            WindowData::BulkDates { selected } => {
+                match key.code {
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        *selected = selected.saturating_sub(1);
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        *selected = (*selected + 1).min(DateShift::ALL.len() - 1);
+                    }
+                    KeyCode::Enter => self.shift_dates(DateShift::ALL[*selected]),
+                    _ => {}
+                }
+                true
+            }
+            WindowData::Templates { selected } => {
+                match key.code {
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        *selected = selected.saturating_sub(1);
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        *selected =
+                            (*selected + 1).min(self.config.templates.len().saturating_sub(1));
+                    }
+                    KeyCode::Enter => self.create_from_template(*selected),
+                    _ => {}
+                }
+                true
+            }
+            WindowData::Links { selected } => {
+                let count = self.selected_task().map_or(0, |task| task.links.len());
+                match key.code {
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        *selected = selected.saturating_sub(1);
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        *selected = (*selected + 1).min(count.saturating_sub(1));
+                    }
+                    KeyCode::Enter => {
+                        let link = self
+                            .selected_task()
+                            .and_then(|task| task.links.get(*selected).cloned());
+                        if let Some(link) = link {
+                            self.open_link(&link);
+                        }
+                    }
+                    KeyCode::Char('d') | KeyCode::Delete => {
+                        if let Some(task) = self.selected_task_mut()
+                            && *selected < task.links.len()
+                        {
+                            task.links.remove(*selected);
+                            *selected = (*selected).min(task.links.len().saturating_sub(1));
+                        }
+                    }
+                    _ => {}
+                }
+                true
+            }
+            WindowData::Input { kind, value } => match key.code {
+                KeyCode::Enter => {
+                    self.submit_input(*kind, value.text().trim());
+                    true
+                }
+                KeyCode::Esc => true,
+                _ => {
+                    edit_input(value, &mut self.registers, key);
+                    true
+                }
+            },
+            WindowData::Inbox { task_id, step } => {
+                // 收件箱处理完毕时隐藏窗口，随后由 handle_key_event 关闭
+                if !self.handle_inbox_key_event(task_id, step, key) {
+                    window.is_visible = false;
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 处理收件箱窗口的按键，返回 false 表示处理结束
+    fn handle_inbox_key_event(
+        &mut self,
+        task_id: &mut u64,
+        step: &mut InboxStep,
+        key: KeyEvent,
+    ) -> bool {
+        let Some(index) = self.tasks.iter().position(|task| task.id == *task_id) else {
+            return false;
+        };
+
+        match step {
+            InboxStep::Menu => match key.code {
+                KeyCode::Char('l') => {
+                    if self.known_lists().is_empty() {
+                        self.status_message =
+                            Some(StatusMessage::info("还没有清单，请在配置的 lists 中添加"));
+                    } else {
+                        *step = InboxStep::List { selected: 0 };
+                    }
+                }
+                KeyCode::Char('c') => *step = InboxStep::Context { selected: 0 },
+                KeyCode::Char('d') => {
+                    let value = self.tasks[index]
+                        .due_date
+                        .map(format_datetime)
+                        .unwrap_or_default();
+                    *step = InboxStep::Due {
+                        value: TextInput::new(value),
+                    };
+                }
+                KeyCode::Char('x') => {
+                    let title = self.tasks[index].title.clone();
+                    self.delete_task(index);
+                    self.status_message = Some(StatusMessage::info(format!("已删除「{title}」")));
+                    return self.next_inbox_item(task_id);
+                }
+                KeyCode::Char('n') | KeyCode::Enter => return self.next_inbox_item(task_id),
+                _ => {}
+            },
+            InboxStep::List { selected } => {
+                let lists = self.known_lists();
+                match key.code {
+                    KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        *selected = (*selected + 1).min(lists.len().saturating_sub(1));
+                    }
+                    KeyCode::Enter => {
+                        let task = &mut self.tasks[index];
+                        let before = task.clone();
+                        task.list = lists.get(*selected).cloned();
+                        task.record_changes(&before);
+                        self.refresh_view();
+                        // 归入清单后离开收件箱，进入下一项
+                        *step = InboxStep::Menu;
+                        return self.next_inbox_item(task_id);
+                    }
+                    _ => {}
+                }
+            }
+            InboxStep::Context { selected } => {
+                let contexts = self.config.contexts();
+                match key.code {
+                    KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        *selected = (*selected + 1).min(contexts.len().saturating_sub(1));
+                    }
+                    KeyCode::Char(' ') => {
+                        if let Some(context) = contexts.get(*selected) {
+                            self.tasks[index].toggle_context(context);
+                        }
+                    }
+                    KeyCode::Enter => *step = InboxStep::Menu,
+                    _ => {}
+                }
+            }
+            InboxStep::Due { value } => match key.code {
+                KeyCode::Enter => {
+                    let now = OffsetDateTime::now_utc();
+                    let input = value.text().trim();
+                    let due = if input.is_empty() {
+                        None
+                    } else {
+                        match parse_due(input, now) {
+                            Some(due) => Some(due),
+                            None => {
+                                self.status_message = Some(StatusMessage::error(format!(
+                                    "无法解析日期 `{input}`，例如 2025-06-01 18:00 或 3d"
+                                )));
+                                return true;
+                            }
+                        }
+                    };
+                    let task = &mut self.tasks[index];
+                    let before = task.clone();
+                    task.due_date = due;
+                    task.update_status();
+                    task.record_changes(&before);
+                    self.refresh_view();
+                    *step = InboxStep::Menu;
+                }
+                _ => {
+                    edit_input(value, &mut self.registers, key);
+                }
+            },
+        }
+        true
+    }
+
+    /// 移到 `task_id` 之后的下一个收件箱任务，没有时返回 false
+    fn next_inbox_item(&mut self, task_id: &mut u64) -> bool {
+        let next = self
+            .inbox()
+            .into_iter()
+            .map(|i| self.tasks[i].id)
+            .find(|&id| id > *task_id);
+        match next {
+            Some(id) => {
+                *task_id = id;
+                true
+            }
+            None => {
+                let remaining = self.inbox().len();
+                let message = if remaining == 0 {
+                    "收件箱已清空".to_string()
+                } else {
+                    format!("收件箱处理完毕，还有 {remaining} 项留待下次")
+                };
+                self.status_message = Some(StatusMessage::info(message));
+                false
+            }
+        }
+    }
+
+    /// 处理详情面板内的键盘事件：j/k 选择复选框，空格/Enter 切换勾选
+    fn handle_details_key_event(&mut self, key: KeyEvent) -> bool {
+        let count = self
+            .selected_task()
+            .map(|task| markdown::task_markers(&task.description).len())
+            .unwrap_or(0);
+
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                if count > 0 {
+                    self.checkbox_cursor = (self.checkbox_cursor + 1) % count;
+                }
+                true
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                if count > 0 {
+                    self.checkbox_cursor = (self.checkbox_cursor + count - 1) % count;
+                }
+                true
+            }
+            KeyCode::Char(' ') | KeyCode::Enter => {
+                let index = self.checkbox_cursor;
+                if let Some(task) = self.selected_task_mut() {
+                    markdown::toggle_task_marker(&mut task.description, index);
+                }
+                // 清单全部勾选时询问是否完成任务
+                if self
+                    .selected_task()
+                    .is_some_and(|task| task.manual_progress.is_none() && task.awaits_completion())
+                {
+                    self.confirm_complete();
+                }
+                true
+            }
+            KeyCode::Char('c') => {
+                self.open_input(InputKind::Comment, String::new());
+                true
+            }
+            KeyCode::Esc => {
+                self.details_focused = false;
+                true
+            }
             _ => false,
         }
     }
 
+    /// 当前选中的任务在 tasks 中的下标
+    pub fn selected_index(&self) -> Option<usize> {
+        self.list_state
+            .selected()
+            .and_then(|row| self.view.get(row).copied())
+    }
+
+    /// 当前选中的任务
+    pub fn selected_task(&self) -> Option<&TodoTask> {
+        self.selected_index().and_then(|i| self.tasks.get(i))
+    }
+
+    fn selected_task_mut(&mut self) -> Option<&mut TodoTask> {
+        self.selected_index().and_then(|i| self.tasks.get_mut(i))
+    }
+
+    /// 按配置中的权重计算任务的紧急度
+    pub fn urgency(&self, task: &TodoTask) -> Urgency {
+        task.urgency(&self.config.urgency, &self.tasks, OffsetDateTime::now_utc())
+    }
+
+    /// 重新计算列表中显示的任务，尽量保持选中同一个任务
+    pub fn refresh_view(&mut self) {
+        let selected = self.selected_index();
+        let now = OffsetDateTime::now_utc();
+
+        self.view = (0..self.tasks.len())
+            .filter(|&i| self.show_future || self.tasks[i].is_actionable(now))
+            .filter(|&i| {
+                self.filter
+                    .as_ref()
+                    .is_none_or(|filter| filter.matches(&self.tasks[i], &self.config.fields))
+            })
+            .filter(|&i| !self.only_mine || self.tasks[i].assignee == self.config.user)
+            .collect();
+        // 稳定排序，值相同的任务保持添加顺序
+        let urgency: Vec<f64> = self
+            .tasks
+            .iter()
+            .map(|task| self.urgency(task).score)
+            .collect();
+        let context = SortContext {
+            tasks: &self.tasks,
+            fields: &self.config.fields,
+            urgency: &urgency,
+        };
+        self.view
+            .sort_by(|&a, &b| self.sort.compare(a, b, &context));
+
+        let row = match selected.and_then(|task| self.view.iter().position(|&i| i == task)) {
+            Some(row) => Some(row),
+            // 选中的任务被隐藏时，停留在原来的行附近
+            None if self.view.is_empty() => None,
+            None => Some(
+                self.list_state
+                    .selected()
+                    .unwrap_or(0)
+                    .min(self.view.len() - 1),
+            ),
+        };
+        if row != self.list_state.selected() {
+            self.checkbox_cursor = 0;
+        }
+        self.list_state.select(row);
+        self.scroll_state = ScrollbarState::new(self.view.len()).position(row.unwrap_or(0));
+    }
+
     // 新增：音乐列表向上移动
     fn music_list_previous(&mut self) {
         let i = match self.music_list_state.selected() {
@@ -609,21 +2527,610 @@ impl App {
         self.music_player_state.playback_state = PlaybackState::Stopped;
     }
 
-    /// 创建新任务
-    fn create_task(&mut self, title: String, description: String) {
+    /// 创建新任务，`template` 为使用的模板下标
+    fn create_task(&mut self, title: String, description: String, template: Option<usize>) {
         // 使用 new() 构造函数创建任务
-        let task = TodoTask::new(title, description);
+        let mut task = TodoTask::new(title, description);
+        task.id = self.next_task_id();
+        task.position = self.next_position();
+        task.created_by = self.config.user.clone();
+        if let Some(template) = template.and_then(|i| self.config.templates.get(i)) {
+            template.apply(&mut task);
+        }
 
         self.tasks.push(task);
 
-        // 更新滚动条
-        self.scroll_state = ScrollbarState::new(self.tasks.len());
+        // 更新列表和滚动条
+        self.refresh_view();
+    }
+
+    /// 打开模板选择窗口
+    fn open_templates(&mut self) {
+        if self.config.templates.is_empty() {
+            self.status_message = Some(StatusMessage::info("配置中没有任务模板"));
+            return;
+        }
+        self.open_window(WindowType::Templates);
+    }
+
+    /// 用模板填充新建任务窗口，标签和截止日期在创建时应用
+    fn create_from_template(&mut self, index: usize) {
+        let Some(template) = self.config.templates.get(index) else {
+            return;
+        };
+        let now = OffsetDateTime::now_utc();
+        let title = template.title_for(now);
+        let description = template.description_for(now);
+
+        self.open_window(WindowType::CreateTask);
+        if let Some(ActiveWindow {
+            data:
+                WindowData::CreateTask {
+                    title: window_title,
+                    description: window_description,
+                    template,
+                    ..
+                },
+            ..
+        }) = &mut self.active_window
+        {
+            window_title.set_text(title);
+            window_description.set_text(description);
+            *template = Some(index);
+        }
+    }
+
+    /// 保存番茄钟设置并为选中的任务开始一个番茄钟
+    fn save_pomodoro_settings(&mut self, duration_index: usize, custom_duration: &str) {
+        // 自定义时间优先，否则使用常用时间
+        self.pomodoro_minutes = custom_duration
+            .parse()
+            .ok()
+            .filter(|minutes| POMODORO_MINUTES_RANGE.contains(minutes))
+            .or_else(|| POMODORO_PRESETS.get(duration_index).copied())
+            .unwrap_or(DEFAULT_POMODORO_MINUTES);
+
+        let task_index = self.selected_index();
+        // 番茄钟会自动计入用时，先停止手动计时避免重复统计
+        if let Some(task) = task_index.and_then(|i| self.tasks.get_mut(i)) {
+            task.stop_tracking();
+        }
+        self.pomodoro = Some(PomodoroSession::new(task_index, self.pomodoro_minutes));
+        self.status_message = Some(StatusMessage::info(format!(
+            "🍅 开始 {} 分钟番茄钟",
+            self.pomodoro_minutes
+        )));
+    }
+
+    /// 结束当前番茄钟，把已专注的时间计入任务
+    fn finish_pomodoro(&mut self) {
+        let Some(session) = self.pomodoro.take() else {
+            return;
+        };
+        let elapsed = session.elapsed();
+        let finished = session.is_finished();
+
+        let Some(task) = session.task_index.and_then(|i| self.tasks.get_mut(i)) else {
+            return;
+        };
+        task.add_time_spent(elapsed);
+        if finished {
+            task.pomodoros_done += 1;
+        }
+
+        let verb = if finished {
+            "番茄钟完成"
+        } else {
+            "番茄钟已停止"
+        };
+        self.status_message = Some(StatusMessage::info(format!(
+            "🍅 {verb}，{} 计入「{}」",
+            format_duration(elapsed),
+            task.title
+        )));
+    }
+
+    /// 开始/停止选中任务的计时，同一时间只计时一个任务
+    fn toggle_tracking(&mut self) {
+        let Some(selected) = self.selected_index() else {
+            return;
+        };
+        if self
+            .tasks
+            .get(selected)
+            .is_none_or(|t| t.status == TaskStatus::Completed)
+        {
+            return;
+        }
+
+        if self.tasks[selected].is_tracking() {
+            self.tasks[selected].stop_tracking();
+            let task = &self.tasks[selected];
+            self.status_message = Some(StatusMessage::info(format!(
+                "⏱ 停止计时「{}」，累计 {}",
+                task.title,
+                format_duration(task.actual_time())
+            )));
+        } else {
+            for task in &mut self.tasks {
+                task.stop_tracking();
+            }
+            self.tasks[selected].start_tracking();
+            self.status_message = Some(StatusMessage::info(format!(
+                "⏱ 开始计时「{}」",
+                self.tasks[selected].title
+            )));
+        }
+    }
+
+    /// 推迟选中任务的开始日期
+    fn defer_selected(&mut self, preset: DeferPreset) {
+        let Some(task) = self.selected_task_mut() else {
+            return;
+        };
+        if let Err(e) = task.defer(preset) {
+            self.status_message = Some(StatusMessage::error(e));
+            return;
+        }
+        let message = match task.scheduled_date {
+            Some(start) => format!("「{}」推迟到 {}", task.title, format_datetime(start)),
+            None => String::new(),
+        };
+        self.last_change = Some(Change::Defer(preset));
+        self.status_message = Some(StatusMessage::info(message));
+        self.refresh_view();
+    }
+
+    /// 复制选中的任务并选中副本
+    fn duplicate_selected(&mut self) {
+        let Some(index) = self.selected_index() else {
+            return;
+        };
+        let mut copy = self.tasks[index].duplicate();
+        copy.id = self.next_task_id();
+        copy.position = self.next_position();
+        copy.created_by = self.config.user.clone();
+        let id = copy.id;
+        // 追加到末尾，避免其他地方保存的下标失效
+        self.tasks.push(copy);
+        self.refresh_view();
+        self.jump_to_task(id);
+        self.status_message = Some(StatusMessage::info(format!("已复制为 #{id}")));
+    }
+
+    /// 设置选中任务的负责人，`None` 表示取消分配
+    fn assign_selected(&mut self, handle: Option<String>) {
+        let message = match &handle {
+            Some(handle) => {
+                let name = self
+                    .config
+                    .member(handle)
+                    .map_or(handle.as_str(), |member| member.display_name());
+                format!("已分配给 {name}")
+            }
+            None => "已取消分配".to_string(),
+        };
+        let Some(task) = self.selected_task_mut() else {
+            return;
+        };
+        if task.assignee == handle {
+            return;
+        }
+        let before = task.clone();
+        task.assignee = handle;
+        task.record_changes(&before);
+        self.refresh_view();
+        self.status_message = Some(StatusMessage::info(message));
+    }
+
+    /// 切换"我的任务"筛选
+    fn toggle_only_mine(&mut self) {
+        if self.config.user.is_none() {
+            self.status_message =
+                Some(StatusMessage::info("配置中没有设置 user，无法筛选我的任务"));
+            return;
+        }
+        self.only_mine = !self.only_mine;
+        self.refresh_view();
+        let message = if self.only_mine {
+            "只显示分配给我的任务"
+        } else {
+            "显示所有人的任务"
+        };
+        self.status_message = Some(StatusMessage::info(message));
+    }
+
+    /// 收件箱中未完成任务的下标，按添加顺序排列
+    pub fn inbox(&self) -> Vec<usize> {
+        (0..self.tasks.len())
+            .filter(|&i| {
+                let task = &self.tasks[i];
+                task.in_inbox() && task.status != TaskStatus::Completed
+            })
+            .collect()
+    }
+
+    /// 可选的清单：先是配置中的，再是任务上出现过的
+    pub fn known_lists(&self) -> Vec<String> {
+        let mut lists = self.config.lists.clone();
+        for list in self.tasks.iter().filter_map(|task| task.list.as_ref()) {
+            if !lists.contains(list) {
+                lists.push(list.clone());
+            }
+        }
+        lists
+    }
+
+    /// 开始逐项处理收件箱
+    fn process_inbox(&mut self) {
+        let Some(&first) = self.inbox().first() else {
+            self.status_message = Some(StatusMessage::info("收件箱是空的"));
+            return;
+        };
+        let task_id = self.tasks[first].id;
+        self.open_window(WindowType::Inbox);
+        if let Some(window) = &mut self.active_window {
+            window.data = WindowData::Inbox {
+                task_id,
+                step: InboxStep::Menu,
+            };
+        }
+    }
+
+    /// 删除任务，并修正其他地方保存的任务下标
+    fn delete_task(&mut self, index: usize) {
+        self.tasks.remove(index);
+        // 删除位置之后的下标前移一位，指向被删除任务的引用直接丢弃
+        let shift = |i: usize| match i.cmp(&index) {
+            std::cmp::Ordering::Less => Some(i),
+            std::cmp::Ordering::Equal => None,
+            std::cmp::Ordering::Greater => Some(i - 1),
+        };
+        if let Some(session) = &mut self.pomodoro {
+            session.task_index = session.task_index.and_then(shift);
+        }
+        self.editor_draft = self
+            .editor_draft
+            .take()
+            .and_then(|(i, draft)| shift(i).map(|i| (i, draft)));
+        self.pending_editor = match self.pending_editor {
+            Some(EditorTarget::Description(i)) => shift(i).map(EditorTarget::Description),
+            Some(EditorTarget::Task(i)) => shift(i).map(EditorTarget::Task),
+            other => other,
+        };
+        self.view.retain(|&i| i != index);
+        for i in &mut self.view {
+            if *i > index {
+                *i -= 1;
+            }
+        }
+        self.refresh_view();
+    }
+
+    /// 删除批量操作的目标任务
+    fn delete_targets(&mut self) {
+        let ids = std::mem::take(&mut self.targets);
+        let mut deleted = 0;
+        for id in ids {
+            // 每次删除后下标都会变化，按 ID 重新查找
+            if let Some(index) = self.tasks.iter().position(|task| task.id == id) {
+                self.delete_task(index);
+                deleted += 1;
+            }
+        }
+        self.status_message = Some(StatusMessage::info(format!("已删除 {deleted} 个任务")));
+    }
+
+    /// 完成批量操作的目标任务，已完成的跳过
+    fn complete_targets(&mut self) {
+        let targets = self.take_targets();
+        self.last_change = Some(Change::Complete(targets.len()));
+        let mut completed = 0;
+        for i in targets {
+            let task = &mut self.tasks[i];
+            if task.status != TaskStatus::Completed {
+                task.complete();
+                completed += 1;
+            }
+        }
+        self.refresh_view();
+        self.status_message = Some(StatusMessage::info(format!("已完成 {completed} 个任务")));
+    }
+
+    /// 把批量操作的目标任务移到清单，`None` 表示放回收件箱
+    fn move_targets_to(&mut self, list: Option<String>) {
+        let targets = self.take_targets();
+        for &i in &targets {
+            let task = &mut self.tasks[i];
+            let before = task.clone();
+            task.list = list.clone();
+            task.record_changes(&before);
+        }
+        self.refresh_view();
+        self.status_message = Some(StatusMessage::info(format!(
+            "已将 {} 个任务移到{}",
+            targets.len(),
+            list.as_deref().unwrap_or("收件箱")
+        )));
+    }
+
+    /// 对批量操作的目标任务调整截止日期
+    fn shift_dates(&mut self, shift: DateShift) {
+        let now = OffsetDateTime::now_utc();
+        let mut changed = 0;
+        for i in self.take_targets() {
+            if shift.apply(&mut self.tasks[i], now) {
+                changed += 1;
+            }
+        }
+        self.refresh_view();
+        self.status_message = Some(StatusMessage::info(format!(
+            "{}：已调整 {changed} 个任务",
+            shift.label()
+        )));
+    }
+
+    /// 询问是否把选中的任务标记为完成
+    fn confirm_complete(&mut self) {
+        if let Some(id) = self.selected_task().map(|task| task.id) {
+            self.confirm(ConfirmAction::CompleteTask(id));
+        }
+    }
+
+    /// 打开确认窗口
+    fn confirm(&mut self, action: ConfirmAction) {
+        let layout = self.get_window_layout(&WindowType::Confirm);
+        self.active_window = Some(ActiveWindow {
+            window_type: WindowType::Confirm,
+            layout,
+            data: WindowData::Confirm { action },
+            is_visible: true,
+        });
+    }
+
+    /// 把指定 ID 的任务标记为完成
+    fn complete_task(&mut self, id: u64) {
+        let Some(task) = self.tasks.iter_mut().find(|task| task.id == id) else {
+            return;
+        };
+        task.complete();
+        self.status_message = Some(StatusMessage::info(format!("「{}」已完成", task.title)));
+        self.refresh_view();
+    }
+
+    /// 打开链接：网址和路径交给外部程序，任务链接跳转到对应任务
+    fn open_link(&mut self, link: &TaskLink) {
+        match link {
+            TaskLink::Url(target) | TaskLink::Path(target) => {
+                if let Err(e) = opener::open(target, self.config.opener.as_deref()) {
+                    self.status_message =
+                        Some(StatusMessage::error(format!("无法打开 {target}: {e}")));
+                }
+            }
+            TaskLink::Task(id) | TaskLink::BlockedBy(id) => self.jump_to_task(*id),
+        }
+    }
+
+    /// 在列表中选中指定 ID 的任务，必要时显示尚未开始的任务
+    fn jump_to_task(&mut self, id: u64) {
+        let Some(index) = self.tasks.iter().position(|task| task.id == id) else {
+            self.status_message = Some(StatusMessage::error(format!("任务 #{id} 不存在")));
+            return;
+        };
+
+        if !self.view.contains(&index) {
+            self.show_future = true;
+            self.refresh_view();
+        }
+        if let Some(row) = self.view.iter().position(|&i| i == index) {
+            self.list_state.select(Some(row));
+            self.scroll_state = self.scroll_state.position(row);
+            self.checkbox_cursor = 0;
+        }
+    }
+
+    /// 新任务的 ID
+    fn next_task_id(&self) -> u64 {
+        self.tasks.iter().map(|task| task.id).max().unwrap_or(0) + 1
+    }
+
+    /// 新任务在手动顺序中排在最后
+    fn next_position(&self) -> u64 {
+        self.tasks
+            .iter()
+            .map(|task| task.position)
+            .max()
+            .unwrap_or(0)
+            + 1
+    }
+
+    /// 手动移动选中的任务
+    fn move_selected(&mut self, movement: Movement) {
+        if let Some(row) = self.list_state.selected() {
+            self.move_rows(row..row + 1, movement);
+        }
+    }
+
+    /// 手动移动列表中连续的若干行
+    ///
+    /// 不在手动排序时，先以当前列表顺序作为手动顺序再移动，列表不会跳动
+    fn move_rows(&mut self, rows: Range<usize>, movement: Movement) {
+        if rows.is_empty() || rows.end > self.view.len() {
+            return;
+        }
+
+        // 只在可见任务原有的位置之间重新分配，隐藏的任务位置不变
+        let mut order = self.view.clone();
+        let mut positions: Vec<u64> = order.iter().map(|&i| self.tasks[i].position).collect();
+        positions.sort_unstable();
+        movement.apply(&mut order, rows);
+        for (&i, position) in order.iter().zip(positions) {
+            self.tasks[i].position = position;
+        }
+
+        if self.sort != SortMode::Manual {
+            self.sort = SortMode::Manual;
+            self.status_message = Some(StatusMessage::info("已切换为手动排序"));
+        }
+        self.refresh_view();
+    }
+
+    /// 打开通用输入窗口
+    fn open_input(&mut self, kind: InputKind, initial: String) {
+        let layout = self.get_window_layout(&WindowType::Input);
+        self.active_window = Some(ActiveWindow {
+            window_type: WindowType::Input,
+            layout,
+            data: WindowData::Input {
+                kind,
+                value: TextInput::new(initial),
+            },
+            is_visible: true,
+        });
     }
 
-    /// 保存番茄钟设置
-    // TODO:
-    fn save_pomodoro_settings(&mut self, duration_index: usize, custom_duration: String) {
-        // 这里实现保存番茄钟设置的逻辑
+    /// 处理输入窗口提交的内容
+    fn submit_input(&mut self, kind: InputKind, value: &str) {
+        match kind {
+            InputKind::Estimate => {
+                let estimate = if value.is_empty() {
+                    None
+                } else {
+                    match Estimate::parse(value) {
+                        Some(estimate) => Some(estimate),
+                        None => {
+                            self.status_message =
+                                Some(StatusMessage::error(format!("无法解析预估用时 `{value}`")));
+                            return;
+                        }
+                    }
+                };
+                if let Some(task) = self.selected_task_mut() {
+                    task.estimate = estimate;
+                    self.last_change = Some(Change::Input(kind, value.to_string(), 1));
+                }
+            }
+            InputKind::Link => {
+                let Some(link) = TaskLink::parse(value) else {
+                    self.status_message =
+                        Some(StatusMessage::error(format!("无法识别的链接 `{value}`")));
+                    return;
+                };
+                if let Some(task) = self.selected_task_mut() {
+                    task.links.push(link);
+                }
+            }
+            InputKind::Filter => {
+                if value.is_empty() {
+                    self.filter = None;
+                } else {
+                    match FieldFilter::parse(value, &self.config.fields) {
+                        Ok(filter) => self.filter = Some(filter),
+                        Err(e) => {
+                            self.status_message = Some(StatusMessage::error(e));
+                            return;
+                        }
+                    }
+                }
+                self.refresh_view();
+            }
+            InputKind::Progress => {
+                let progress = if value.is_empty() {
+                    None
+                } else {
+                    match parse_progress(value) {
+                        Some(progress) => Some(progress),
+                        None => {
+                            self.status_message = Some(StatusMessage::error(format!(
+                                "进度应为 0–100，而不是 `{value}`"
+                            )));
+                            return;
+                        }
+                    }
+                };
+                if let Some(task) = self.selected_task_mut() {
+                    task.manual_progress = progress;
+                    self.last_change = Some(Change::Input(kind, value.to_string(), 1));
+                }
+                if self
+                    .selected_task()
+                    .is_some_and(|task| task.awaits_completion())
+                {
+                    self.confirm_complete();
+                }
+            }
+            InputKind::Tags => {
+                let changes = match TagChanges::parse(value) {
+                    Ok(changes) => changes,
+                    Err(e) => {
+                        self.status_message = Some(StatusMessage::error(e));
+                        return;
+                    }
+                };
+                let targets = self.take_targets();
+                self.last_change = Some(Change::Input(kind, value.to_string(), targets.len()));
+                let changed = targets
+                    .iter()
+                    .filter(|&&i| changes.apply(&mut self.tasks[i]))
+                    .count();
+                self.refresh_view();
+                self.status_message = Some(StatusMessage::info(format!(
+                    "已修改 {changed} 个任务的标签"
+                )));
+            }
+            InputKind::Due => {
+                let now = OffsetDateTime::now_utc();
+                let due = if value.is_empty() {
+                    None
+                } else {
+                    match parse_due(value, now) {
+                        Some(due) => Some(due),
+                        None => {
+                            self.status_message = Some(StatusMessage::error(format!(
+                                "无法解析日期 `{value}`，例如 2025-06-01 18:00 或 3d"
+                            )));
+                            return;
+                        }
+                    }
+                };
+                let targets = self.take_targets();
+                self.last_change = Some(Change::Input(kind, value.to_string(), targets.len()));
+                for &i in &targets {
+                    let task = &mut self.tasks[i];
+                    let before = task.clone();
+                    task.due_date = due;
+                    task.update_status();
+                    task.record_changes(&before);
+                }
+                self.refresh_view();
+                self.status_message = Some(StatusMessage::info(format!(
+                    "已设置 {} 个任务的截止日期",
+                    targets.len()
+                )));
+            }
+            InputKind::Capture => {
+                let (title, contexts) = extract_contexts(value, &self.config.contexts());
+                if title.is_empty() {
+                    return;
+                }
+                self.create_task(title, String::new(), None);
+                if let Some(task) = self.tasks.last_mut() {
+                    task.contexts.extend(contexts);
+                }
+                let count = self.inbox().len();
+                self.status_message = Some(StatusMessage::info(format!(
+                    "已记录到收件箱（共 {count} 项，I 处理）"
+                )));
+            }
+            InputKind::Comment => {
+                if value.is_empty() {
+                    return;
+                }
+                if let Some(task) = self.selected_task_mut() {
+                    task.add_comment(value);
+                }
+            }
+        }
     }
 
     /// 打开新窗口
@@ -631,14 +3138,15 @@ impl App {
         let layout = self.get_window_layout(&window_type);
         let data = match window_type {
             WindowType::CreateTask => WindowData::CreateTask {
-                title: String::new(),
-                description: String::new(),
+                title: TextInput::default(),
+                description: TextInput::multiline(""),
+                fields: vec![TextInput::default(); self.config.fields.len()],
                 current_field: 0,
-                cursor_position: 0, // 新增
+                template: None,
             },
             WindowType::PomodoroSettings => WindowData::PomodoroSettings {
                 selected_duration: 2, // 默认25分钟
-                custom_duration: String::new(),
+                custom_duration: TextInput::default(),
                 current_focus: 0,
             },
             WindowType::Settings => WindowData::Settings {
@@ -647,6 +3155,26 @@ impl App {
                 play_on_finish: false,       // 可以从配置读取
                 current_focus: 0,
             },
+            WindowType::Defer => WindowData::Defer { selected: 1 }, // 默认推迟到明天
+            WindowType::Links => WindowData::Links { selected: 0 },
+            WindowType::Templates => WindowData::Templates { selected: 0 },
+            WindowType::BulkDates => WindowData::BulkDates { selected: 0 },
+            WindowType::MoveToList => WindowData::MoveToList { selected: 0 },
+            // 默认选中当前负责人
+            WindowType::Assignee => WindowData::Assignee {
+                selected: self
+                    .selected_task()
+                    .and_then(|task| task.assignee.as_deref())
+                    .and_then(|handle| {
+                        self.config
+                            .roster
+                            .iter()
+                            .position(|member| member.handle == handle)
+                    })
+                    .map_or(0, |i| i + 1),
+            },
+            // 确认窗口由 confirm_complete 等方法直接创建
+            WindowType::Confirm => WindowData::Empty,
             _ => WindowData::Empty,
         };
 
@@ -658,11 +3186,21 @@ impl App {
         });
     }
 
+    /// 选中列表中的某一行
+    fn select_row(&mut self, row: usize) {
+        self.list_state.select(Some(row));
+        self.scroll_state = self.scroll_state.position(row);
+        self.checkbox_cursor = 0;
+    }
+
     // 修改 next 方法
     fn next(&mut self) {
+        if self.view.is_empty() {
+            return;
+        }
         let i = match self.list_state.selected() {
             Some(i) => {
-                if i >= self.tasks.len() - 1 {
+                if i >= self.view.len() - 1 {
                     0
                 } else {
                     i + 1
@@ -672,14 +3210,18 @@ impl App {
         };
         self.list_state.select(Some(i));
         self.scroll_state = self.scroll_state.position(i); // <--- 同步位置
+        self.checkbox_cursor = 0;
     }
 
     // 修改 previous 方法
     fn previous(&mut self) {
+        if self.view.is_empty() {
+            return;
+        }
         let i = match self.list_state.selected() {
             Some(i) => {
                 if i == 0 {
-                    self.tasks.len() - 1
+                    self.view.len() - 1
                 } else {
                     i - 1
                 }
@@ -688,6 +3230,7 @@ impl App {
         };
         self.list_state.select(Some(i));
         self.scroll_state = self.scroll_state.position(i); // <--- 同步位置
+        self.checkbox_cursor = 0;
     }
 
     /// 关闭当前窗口
@@ -735,10 +3278,14 @@ impl App {
                 }
             }
 
-            WindowType::Settings => {
-                // 设置窗口
-                let width = (term_width as f32 * 0.75) as u16;
-                let height = (term_height as f32 * 0.9) as u16;
+            WindowType::Defer | WindowType::BulkDates => {
+                // 推迟和批量日期窗口只列出几个预设
+                let width = (term_width as f32 * 0.3) as u16;
+                let count = match window_type {
+                    WindowType::Defer => DeferPreset::ALL.len(),
+                    _ => DateShift::ALL.len(),
+                };
+                let height = (count as u16 + 2).min(term_height);
                 let x = (term_width - width) / 2;
                 let y = (term_height - height) / 2;
                 WindowLayout {
@@ -749,10 +3296,62 @@ impl App {
                 }
             }
 
-            _ => {
-                // 默认
-                let width = (term_width as f32 * 0.7) as u16;
-                let height = (term_height as f32 * 0.7) as u16;
+            WindowType::Links
+            | WindowType::Templates
+            | WindowType::Assignee
+            | WindowType::MoveToList => {
+                // 高度随链接、模板、成员或清单数量变化
+                let count = match window_type {
+                    WindowType::MoveToList => self.known_lists().len() + 1,
+                    WindowType::Links => self.selected_task().map_or(1, |task| task.links.len()),
+                    WindowType::Assignee => self.config.roster.len() + 1,
+                    _ => self.config.templates.len(),
+                };
+                let width = (term_width as f32 * 0.5) as u16;
+                let height = (count as u16 + 2).min(term_height);
+                let x = (term_width - width) / 2;
+                let y = (term_height - height) / 2;
+                WindowLayout {
+                    x,
+                    y,
+                    width,
+                    height,
+                }
+            }
+
+            WindowType::Inbox => {
+                // 收件箱窗口要容纳任务信息和清单、情境列表
+                let options = self.known_lists().len().max(self.config.contexts().len());
+                let width = (term_width as f32 * 0.5) as u16;
+                let height = (options as u16 + 9).min(term_height);
+                let x = (term_width - width) / 2;
+                let y = (term_height - height) / 2;
+                WindowLayout {
+                    x,
+                    y,
+                    width,
+                    height,
+                }
+            }
+
+            WindowType::Input | WindowType::Confirm => {
+                // 输入和确认窗口只需要一行
+                let width = (term_width as f32 * 0.5) as u16;
+                let height = 3.min(term_height);
+                let x = (term_width - width) / 2;
+                let y = (term_height - height) / 2;
+                WindowLayout {
+                    x,
+                    y,
+                    width,
+                    height,
+                }
+            }
+
+            WindowType::Settings => {
+                // 设置窗口
+                let width = (term_width as f32 * 0.75) as u16;
+                let height = (term_height as f32 * 0.9) as u16;
                 let x = (term_width - width) / 2;
                 let y = (term_height - height) / 2;
                 WindowLayout {
@@ -806,3 +3405,55 @@ impl App {
         }
     }
 }
+
+impl ModeHandler for App {
+    fn handle_mode_aware_event(&mut self, event: ModeAwareEvent) -> bool {
+        match event {
+            ModeAwareEvent::Key(key) => match self.mode.current() {
+                AppMode::Normal => self.handle_normal_key(key),
+                // 插入模式下按键只交给正在输入的窗口
+                AppMode::Insert => match self.translate_insert_key(key) {
+                    Some(key) => self.handle_active_window_key(key),
+                    None => true,
+                },
+                // 可视模式中打开的确认窗口优先处理按键
+                AppMode::Visual => {
+                    self.handle_active_window_key(key) || self.handle_mapped_key(key)
+                }
+                AppMode::Command | AppMode::Search => self.handle_prompt_key(key),
+            },
+            ModeAwareEvent::ModeChange(mode) => {
+                self.enter_mode(mode);
+                true
+            }
+            ModeAwareEvent::Command(command) => self.execute_command(&command),
+            ModeAwareEvent::Search(query) => self.search(&query),
+        }
+    }
+}
+
+/// 把按键交给输入框：Ctrl-Y 粘贴最近删除或复制的内容；按单词或整行删除的文本存入小删除寄存器，
+/// 不同步到系统剪贴板，Alt-W 复制的文本存入无名寄存器
+fn edit_input(input: &mut TextInput, registers: &mut Registers, key: KeyEvent) -> bool {
+    if key.code == KeyCode::Char('y') && key.modifiers == KeyModifiers::CONTROL {
+        if let Some(content) = registers.latest() {
+            input.paste(&content.text());
+        }
+        return true;
+    }
+    let handled = input.handle_key(key);
+    if let Some(text) = input.take_killed() {
+        registers.store_small_delete(text);
+    }
+    if let Some(text) = input.take_copied() {
+        registers.store(UNNAMED_REGISTER, RegisterContent::Text(text));
+    }
+    handled
+}
+
+/// Ctrl-d/Ctrl-u 移动的行数：大约是列表高度的一半
+fn half_page() -> usize {
+    let height = terminal_size::terminal_size().map_or(30, |(_, height)| height.0 as usize);
+    // 减去状态栏和列表边框
+    (height.saturating_sub(3) / 2).max(1)
+}
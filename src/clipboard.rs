@@ -0,0 +1,14 @@
+use std::io::{self, Write};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+/// 用 OSC 52 转义序列把文本写入系统剪贴板，由终端完成复制，
+/// 所以经由 SSH 也能使用，不依赖 xclip、pbcopy 等外部程序
+///
+/// 终端需要支持 OSC 52，在 tmux 中还需要开启 `set-clipboard`
+pub fn copy(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+    stdout.flush()
+}
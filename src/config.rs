@@ -0,0 +1,196 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::models::{
+    AppMode, DEFAULT_CONTEXTS, FieldDef, FieldType, KeyBinding, Keymaps, Member, TaskTemplate,
+    UrgencyWeights, parse_keys,
+};
+
+/// front matter 中的内置字段名，自定义字段不能与之重名
+const RESERVED_FIELDS: [&str; 18] = [
+    "title",
+    "status",
+    "assignee",
+    "list",
+    "contexts",
+    "priority",
+    "tags",
+    "scheduled",
+    "due",
+    "estimate",
+    "progress",
+    "link",
+    "id",
+    "position",
+    "created",
+    "created_by",
+    "comment",
+    "history",
+];
+
+/// 用户配置，读取自 config.toml，未填写的字段使用默认值
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// 打开链接和附件的命令，例如 "xdg-open"、"open"，为空时按平台选择
+    pub opener: Option<String>,
+    /// 本机用户的 handle，用于"我的任务"筛选和记录创建者
+    pub user: Option<String>,
+    /// 团队成员名单，分配负责人时从中选择
+    pub roster: Vec<Member>,
+    /// 处理收件箱时可选的清单，任务上已有的清单也会列出
+    pub lists: Vec<String>,
+    /// 可选的情境，不带 @；为空时使用 home、office、phone
+    pub contexts: Vec<String>,
+    /// 自定义字段，在任务编辑器和详情中按声明顺序显示
+    pub fields: Vec<FieldDef>,
+    /// 紧急度权重
+    pub urgency: UrgencyWeights,
+    /// 新建任务时可选的模板
+    pub templates: Vec<TaskTemplate>,
+    /// 预先定义的宏，启动时放入对应的寄存器，用 g@ 执行，例如 `t = ":tag +triage<CR>j"`
+    pub macros: BTreeMap<char, String>,
+    /// 按键映射，读取自配置目录下的 keymap/<模式>.toml，
+    /// 即 normal、visual、insert、command 和 search 五个文件
+    #[serde(skip)]
+    pub keymaps: Keymaps,
+}
+
+impl Config {
+    /// 配置文件路径：优先 `$DY_TODO_CONFIG`，其次系统配置目录下的 dy-todo/config.toml
+    pub fn path() -> Option<PathBuf> {
+        if let Some(path) = std::env::var_os("DY_TODO_CONFIG") {
+            return Some(PathBuf::from(path));
+        }
+
+        let base = if cfg!(windows) {
+            std::env::var_os("APPDATA").map(PathBuf::from)
+        } else {
+            std::env::var_os("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .or_else(|| {
+                    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config"))
+                })
+        };
+        base.map(|dir| dir.join("dy-todo").join("config.toml"))
+    }
+
+    /// 读取配置，同时返回需要提示的错误：配置文件有误时使用默认配置，按键映射有误时只使用默认按键
+    pub fn load() -> (Self, Option<String>) {
+        let Some(path) = Self::path() else {
+            return (Self::default(), None);
+        };
+        let mut config = match Self::read(&path) {
+            Ok(config) => config,
+            Err(e) => return (Self::default(), Some(e)),
+        };
+        // 按键映射有误时只退回默认按键，其余配置照常使用
+        let mut error = None;
+        if let Some(dir) = path.parent() {
+            match load_keymaps(&dir.join("keymap")) {
+                Ok(keymaps) => config.keymaps = keymaps,
+                Err(e) => error = Some(format!("{e}，已使用默认按键")),
+            }
+        }
+        (config, error)
+    }
+
+    /// 读取并校验配置文件，文件不存在时使用默认配置
+    fn read(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => {
+                let config: Self = toml::from_str(&content)
+                    .map_err(|e| format!("配置 {} 格式错误: {e}", path.display()))?;
+                config
+                    .validate()
+                    .map_err(|e| format!("配置 {} 有误: {e}", path.display()))?;
+                Ok(config)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("无法读取配置 {}: {e}", path.display())),
+        }
+    }
+
+    /// 按 handle 查找团队成员
+    pub fn member(&self, handle: &str) -> Option<&Member> {
+        self.roster.iter().find(|member| member.handle == handle)
+    }
+
+    /// 可选的情境
+    pub fn contexts(&self) -> Vec<String> {
+        if self.contexts.is_empty() {
+            DEFAULT_CONTEXTS.iter().map(|c| c.to_string()).collect()
+        } else {
+            self.contexts.clone()
+        }
+    }
+
+    /// 检查自定义字段、情境、模板和宏
+    fn validate(&self) -> Result<(), String> {
+        for (i, def) in self.fields.iter().enumerate() {
+            let name = def.name.as_str();
+            if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == ':') {
+                return Err(format!("字段名 `{name}` 不能为空或包含空白、冒号"));
+            }
+            if RESERVED_FIELDS.contains(&name) {
+                return Err(format!("字段名 `{name}` 与内置字段重名"));
+            }
+            if self.fields[..i].iter().any(|other| other.name == name) {
+                return Err(format!("字段 `{name}` 重复定义"));
+            }
+            if def.kind == FieldType::Enum && def.options.is_empty() {
+                return Err(format!("enum 字段 `{name}` 需要 options"));
+            }
+        }
+        for context in &self.contexts {
+            if context.is_empty() || context.contains(|c: char| c.is_whitespace() || c == '@') {
+                return Err(format!("情境 `{context}` 不能为空或包含空白、@"));
+            }
+        }
+        for template in &self.templates {
+            if template.name.is_empty() {
+                return Err("模板必须有 name".to_string());
+            }
+            template.due_offset()?;
+        }
+        for (name, keys) in &self.macros {
+            if !name.is_ascii_lowercase() {
+                return Err(format!("宏 `{name}` 的名称只能是 a–z"));
+            }
+            parse_keys(keys).map_err(|e| format!("宏 `{name}` 有误: {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// 读取每个模式的按键映射文件，覆盖默认按键；文件不存在时使用默认按键
+///
+/// 文件中每行是 `动作 = "按键"` 或 `动作 = ["按键", ...]`，空列表表示取消绑定
+fn load_keymaps(dir: &Path) -> Result<Keymaps, String> {
+    let mut keymaps = Keymaps::default();
+    for (mode, file) in [
+        (AppMode::Normal, "normal.toml"),
+        (AppMode::Visual, "visual.toml"),
+        (AppMode::Insert, "insert.toml"),
+        (AppMode::Command, "command.toml"),
+        (AppMode::Search, "search.toml"),
+    ] {
+        let path = dir.join(file);
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(format!("无法读取按键映射 {}: {e}", path.display())),
+        };
+        let overrides: BTreeMap<String, KeyBinding> = toml::from_str(&content)
+            .map_err(|e| format!("按键映射 {} 格式错误: {e}", path.display()))?;
+        let keymap = keymaps.get_mut(mode);
+        keymap
+            .apply(mode, &overrides)
+            .and_then(|()| keymap.check_conflicts())
+            .map_err(|e| format!("按键映射 {} 有误: {e}", path.display()))?;
+    }
+    Ok(keymaps)
+}
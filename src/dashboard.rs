@@ -1,5 +1,6 @@
 use crate::models::AsciiArt;
 use crate::models::TokyoNight;
+use crate::models::{Action, Keymap};
 use ratatui::{
     Frame,
     layout::{Alignment, Rect},
@@ -11,7 +12,7 @@ use ratatui::{
 pub struct Dashboard;
 
 impl Dashboard {
-    pub fn render(area: Rect, frame: &mut Frame, version: &str) {
+    pub fn render(area: Rect, frame: &mut Frame, version: &str, keymap: &Keymap) {
         // 创建垂直布局，将屏幕分成三部分
         let layout = ratatui::layout::Layout::vertical([
             ratatui::layout::Constraint::Percentage(30), // 上部放 ASCII Art
@@ -49,23 +50,27 @@ impl Dashboard {
             height: 3,
         };
 
+        // 常用操作的按键来自按键映射，自定义按键后提示也随之改变
+        let mut shortcuts = vec!["使用 ".fg(Color::White)];
+        for action in [Action::NewTask, Action::Pomodoro, Action::Help] {
+            if let Some(key) = keymap.key_for(action) {
+                shortcuts.push(key.fg(Color::Rgb(100, 255, 100)).bold());
+                shortcuts.push(format!(" {} | ", action.description()).fg(Color::White));
+            }
+        }
+        let quit = keymap
+            .key_for(Action::Quit)
+            .unwrap_or_else(|| ":q".to_string());
+        shortcuts.push(quit.fg(Color::Rgb(255, 100, 100)).bold());
+        shortcuts.push(" 退出".fg(Color::White));
+
         let footer_text = vec![
             Line::from(vec![
                 "按 ".fg(Color::White),
                 "任意键".fg(Color::Rgb(255, 200, 100)).bold(),
                 " 继续".fg(Color::White),
             ]),
-            Line::from(vec![
-                "使用 ".fg(Color::White),
-                "a".fg(Color::Rgb(100, 255, 100)).bold(),
-                " 创建任务 | ".fg(Color::White),
-                "p".fg(Color::Rgb(100, 255, 100)).bold(),
-                " 番茄钟 | ".fg(Color::White),
-                "j/k".fg(Color::Rgb(100, 255, 100)).bold(),
-                " 导航 | ".fg(Color::White),
-                "q".fg(Color::Rgb(255, 100, 100)).bold(),
-                " 退出".fg(Color::White),
-            ]),
+            Line::from(shortcuts),
         ];
 
         let footer_paragraph = Paragraph::new(footer_text)
@@ -0,0 +1,108 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crossterm::{
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use ratatui::DefaultTerminal;
+
+/// 临时文件名冲突时最多尝试的次数
+const TEMP_FILE_ATTEMPTS: u32 = 16;
+
+/// 外部编辑器要编辑的内容
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorTarget {
+    /// 只编辑任务描述
+    Description(usize),
+    /// 以 front matter 文档编辑整个任务
+    Task(usize),
+    /// 创建任务窗口中的描述字段
+    CreateTaskDescription,
+}
+
+/// 暂停 TUI，用 `$VISUAL` / `$EDITOR` 编辑文本，退出编辑器后恢复终端并返回编辑结果
+pub fn edit(terminal: &mut DefaultTerminal, initial: &str) -> io::Result<String> {
+    let path = create_temp_file(initial)?;
+
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen)?;
+
+    let status = spawn_editor(&path);
+
+    // 无论编辑器是否成功启动都要恢复终端
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    enable_raw_mode()?;
+    terminal.clear()?;
+
+    let status = status?;
+    let result = if status.success() {
+        fs::read_to_string(&path)
+    } else {
+        Err(io::Error::other(format!("编辑器异常退出: {status}")))
+    };
+    let _ = fs::remove_file(&path);
+    result
+}
+
+/// 在临时目录中独占创建编辑用的文件，文件名含进程号和时间戳，已存在时换一个名字，
+/// 避免写入他人预先放置的文件或符号链接
+fn create_temp_file(content: &str) -> io::Result<PathBuf> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos());
+    for attempt in 0..TEMP_FILE_ATTEMPTS {
+        let name = format!("dy-todo-{}-{nanos:x}-{attempt}.md", std::process::id());
+        let path = std::env::temp_dir().join(name);
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        // 只有自己可以读写
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        match options.open(&path) {
+            Ok(mut file) => {
+                if let Err(e) = file.write_all(content.as_bytes()) {
+                    let _ = fs::remove_file(&path);
+                    return Err(e);
+                }
+                return Ok(path);
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        "无法创建编辑用的临时文件",
+    ))
+}
+
+fn spawn_editor(path: &Path) -> io::Result<std::process::ExitStatus> {
+    let command = editor_command();
+    let mut parts = command.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+
+    Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .map_err(|e| io::Error::new(e.kind(), format!("无法启动编辑器 `{program}`: {e}")))
+}
+
+/// 依次读取 `$VISUAL`、`$EDITOR`，都没有时使用平台默认编辑器
+fn editor_command() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| {
+            if cfg!(windows) {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        })
+}
@@ -1,6 +1,11 @@
 mod app;
+mod clipboard;
+mod config;
 mod dashboard;
+mod editor;
+mod markdown;
 mod models;
+mod opener;
 mod ui;
 
 use app::App;
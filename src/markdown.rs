@@ -0,0 +1,275 @@
+use std::ops::Range;
+
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+use crate::models::TokyoNight;
+
+/// 代码块/行内代码的背景色
+const CODE_BG: Color = Color::Rgb(30, 32, 48);
+
+fn parser_options() -> Options {
+    Options::ENABLE_TASKLISTS | Options::ENABLE_STRIKETHROUGH
+}
+
+/// 把 Markdown 文本渲染成带样式的行
+///
+/// `selected_checkbox` 为详情面板中当前选中的复选框序号，会被反色高亮
+pub fn render(text: &str, selected_checkbox: Option<usize>) -> Vec<Line<'static>> {
+    let mut renderer = Renderer {
+        selected_checkbox,
+        ..Renderer::default()
+    };
+
+    for event in Parser::new_ext(text, parser_options()) {
+        renderer.handle(event);
+    }
+    renderer.flush();
+
+    // 去掉末尾多余的空行
+    while renderer
+        .lines
+        .last()
+        .is_some_and(|line| line.spans.is_empty())
+    {
+        renderer.lines.pop();
+    }
+    renderer.lines
+}
+
+/// 返回所有复选框标记（`[ ]` / `[x]`）在文本中的字节范围
+pub fn task_markers(text: &str) -> Vec<Range<usize>> {
+    Parser::new_ext(text, parser_options())
+        .into_offset_iter()
+        .filter(|(event, _)| matches!(event, Event::TaskListMarker(_)))
+        .map(|(_, range)| range)
+        .collect()
+}
+
+/// 返回（已勾选数, 复选框总数）
+pub fn checklist_progress(text: &str) -> (usize, usize) {
+    Parser::new_ext(text, parser_options()).fold((0, 0), |(checked, total), event| match event {
+        Event::TaskListMarker(true) => (checked + 1, total + 1),
+        Event::TaskListMarker(false) => (checked, total + 1),
+        _ => (checked, total),
+    })
+}
+
+/// 切换第 `index` 个复选框的勾选状态，返回切换后是否为已勾选
+pub fn toggle_task_marker(text: &mut String, index: usize) -> Option<bool> {
+    let range = task_markers(text).into_iter().nth(index)?;
+    let checked = !text[range.clone()].eq_ignore_ascii_case("[x]");
+    let marker = if checked { "[x]" } else { "[ ]" };
+    text.replace_range(range, marker);
+    Some(checked)
+}
+
+#[derive(Default)]
+struct Renderer {
+    lines: Vec<Line<'static>>,
+    current: Vec<Span<'static>>,
+    styles: Vec<Style>,
+    // 嵌套列表：None 为无序列表，Some(n) 为有序列表的下一个序号
+    lists: Vec<Option<u64>>,
+    // 当前列表项的项目符号在 current 中的位置，遇到复选框时替换
+    bullet: Option<usize>,
+    in_code_block: bool,
+    checkbox_count: usize,
+    selected_checkbox: Option<usize>,
+}
+
+impl Renderer {
+    fn style(&self) -> Style {
+        self.styles
+            .iter()
+            .fold(Style::default(), |acc, style| acc.patch(*style))
+    }
+
+    fn flush(&mut self) {
+        if !self.current.is_empty() {
+            self.lines
+                .push(Line::from(std::mem::take(&mut self.current)));
+        }
+    }
+
+    fn blank_line(&mut self) {
+        if self.lines.last().is_some_and(|line| !line.spans.is_empty()) {
+            self.lines.push(Line::default());
+        }
+    }
+
+    fn handle(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => {
+                if self.in_code_block {
+                    for line in text.lines() {
+                        self.lines.push(Line::from(vec![
+                            Span::styled(" │ ", Style::default().fg(TokyoNight::GRAY)),
+                            Span::styled(
+                                line.to_string(),
+                                Style::default().fg(TokyoNight::CYAN).bg(CODE_BG),
+                            ),
+                        ]));
+                    }
+                } else {
+                    let style = self.style();
+                    self.current.push(Span::styled(text.to_string(), style));
+                }
+            }
+            Event::Code(code) => {
+                self.current.push(Span::styled(
+                    code.to_string(),
+                    Style::default().fg(TokyoNight::ORANGE).bg(CODE_BG),
+                ));
+            }
+            Event::SoftBreak => {
+                let style = self.style();
+                self.current.push(Span::styled(" ", style));
+            }
+            Event::HardBreak => self.flush(),
+            Event::Rule => {
+                self.flush();
+                self.lines.push(Line::styled(
+                    "─".repeat(24),
+                    Style::default().fg(TokyoNight::GRAY),
+                ));
+            }
+            Event::TaskListMarker(checked) => {
+                let index = self.checkbox_count;
+                self.checkbox_count += 1;
+
+                let (icon, color) = if checked {
+                    ("☑ ", Color::Green)
+                } else {
+                    ("☐ ", Color::White)
+                };
+                let mut style = Style::default().fg(color);
+                if self.selected_checkbox == Some(index) {
+                    style = style.add_modifier(Modifier::REVERSED | Modifier::BOLD);
+                }
+                let span = Span::styled(icon, style);
+
+                // 用复选框替换项目符号
+                match self.bullet.take() {
+                    Some(pos) if pos < self.current.len() => self.current[pos] = span,
+                    _ => self.current.push(span),
+                }
+            }
+            Event::Html(html) | Event::InlineHtml(html) => {
+                self.current.push(Span::styled(
+                    html.to_string(),
+                    Style::default().fg(TokyoNight::GRAY),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Heading { level, .. } => {
+                self.flush();
+                let mut style = Style::default().fg(TokyoNight::CYAN).bold();
+                if level == HeadingLevel::H1 {
+                    style = style.add_modifier(Modifier::UNDERLINED);
+                }
+                self.styles.push(style);
+            }
+            Tag::Emphasis => self.styles.push(Style::default().italic()),
+            Tag::Strong => self.styles.push(Style::default().bold()),
+            Tag::Strikethrough => self.styles.push(Style::default().crossed_out()),
+            Tag::Link { .. } => self.styles.push(
+                Style::default()
+                    .fg(TokyoNight::MAGENTA)
+                    .add_modifier(Modifier::UNDERLINED),
+            ),
+            Tag::BlockQuote(_) => {
+                self.flush();
+                self.styles
+                    .push(Style::default().fg(TokyoNight::GRAY).italic());
+                self.current
+                    .push(Span::styled("▎ ", Style::default().fg(TokyoNight::GRAY)));
+            }
+            Tag::CodeBlock(kind) => {
+                self.flush();
+                self.in_code_block = true;
+                if let CodeBlockKind::Fenced(lang) = kind
+                    && !lang.is_empty()
+                {
+                    self.lines.push(Line::styled(
+                        format!(" ╭ {lang}"),
+                        Style::default().fg(TokyoNight::GRAY),
+                    ));
+                }
+            }
+            Tag::List(start) => {
+                self.flush();
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush();
+                let depth = self.lists.len().saturating_sub(1);
+                let bullet = match self.lists.last_mut() {
+                    Some(Some(n)) => {
+                        let bullet = format!("{n}. ");
+                        *n += 1;
+                        bullet
+                    }
+                    _ => "• ".to_string(),
+                };
+                self.current.push(Span::raw("  ".repeat(depth)));
+                self.current.push(Span::styled(
+                    bullet,
+                    Style::default().fg(TokyoNight::ORANGE),
+                ));
+                self.bullet = Some(self.current.len() - 1);
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Heading(_) => {
+                self.styles.pop();
+                self.flush();
+                self.blank_line();
+            }
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link => {
+                self.styles.pop();
+            }
+            TagEnd::BlockQuote(_) => {
+                self.styles.pop();
+                self.flush();
+                self.blank_line();
+            }
+            TagEnd::Paragraph => {
+                self.flush();
+                if self.lists.is_empty() {
+                    self.blank_line();
+                }
+            }
+            TagEnd::CodeBlock => {
+                self.in_code_block = false;
+                self.blank_line();
+            }
+            TagEnd::List(_) => {
+                self.flush();
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.blank_line();
+                }
+            }
+            TagEnd::Item => {
+                self.bullet = None;
+                self.flush();
+            }
+            _ => {}
+        }
+    }
+}
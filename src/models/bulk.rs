@@ -0,0 +1,96 @@
+use time::{Duration, OffsetDateTime, Time};
+
+use super::{ActivityKind, TaskStatus, TodoTask};
+
+/// 批量调整截止日期的操作，作用于当前列表中的任务
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateShift {
+    OverdueToToday, // 逾期的任务改为今天截止
+    NextDay,        // 截止日期顺延一天
+    ClearDue,       // 清除截止日期
+}
+
+impl DateShift {
+    pub const ALL: [Self; 3] = [Self::OverdueToToday, Self::NextDay, Self::ClearDue];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::OverdueToToday => "逾期任务改为今天截止",
+            Self::NextDay => "截止日期 +1 天",
+            Self::ClearDue => "清除截止日期",
+        }
+    }
+
+    /// 应用到单个任务上，已完成的任务不受影响；返回任务是否被修改
+    pub fn apply(&self, task: &mut TodoTask, now: OffsetDateTime) -> bool {
+        if task.status == TaskStatus::Completed {
+            return false;
+        }
+        let Some(due) = task.due_date else {
+            return false;
+        };
+
+        let to = match self {
+            Self::OverdueToToday if due < now => {
+                // 与只填日期时一致，按当天 23:59 截止
+                Some(now.replace_time(Time::MIDNIGHT) + Duration::minutes(23 * 60 + 59))
+            }
+            Self::OverdueToToday => return false,
+            // 超出可表示的日期范围时不修改
+            Self::NextDay => match due.checked_add(Duration::days(1)) {
+                Some(next) => Some(next),
+                None => return false,
+            },
+            Self::ClearDue => None,
+        };
+
+        task.record(ActivityKind::DueChanged {
+            from: Some(due),
+            to,
+        });
+        task.due_date = to;
+        task.update_status();
+        true
+    }
+}
+
+/// 批量修改标签，`+tag` 或 `tag` 添加，`-tag` 移除，例如 `+urgent -later`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagChanges {
+    pub add: Vec<String>,
+    pub remove: Vec<String>,
+}
+
+impl TagChanges {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut changes = Self::default();
+        for word in input.split_whitespace() {
+            let (list, name) = match word.strip_prefix('-') {
+                Some(name) => (&mut changes.remove, name),
+                None => (&mut changes.add, word.strip_prefix('+').unwrap_or(word)),
+            };
+            let name = name.trim_start_matches('#');
+            if name.is_empty() {
+                return Err(format!("标签 `{word}` 为空"));
+            }
+            list.push(name.to_string());
+        }
+        if changes.add.is_empty() && changes.remove.is_empty() {
+            return Err("请输入 +标签 或 -标签".to_string());
+        }
+        Ok(changes)
+    }
+
+    /// 应用到单个任务上并记录历史，返回任务是否被修改
+    pub fn apply(&self, task: &mut TodoTask) -> bool {
+        let before = task.clone();
+        for tag in &self.remove {
+            task.remove_tag(tag);
+        }
+        for tag in &self.add {
+            task.add_tag(tag.clone());
+        }
+        task.record_changes(&before);
+        task.tags != before.tags
+    }
+}
@@ -0,0 +1,240 @@
+use std::ops::Range;
+
+use super::FieldDef;
+
+/// 命令模式中可用的命令
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandKind {
+    Add,         // :add 标题
+    Done,        // :[范围]done
+    Delete,      // :[范围]delete
+    Tag,         // :[范围]tag +x -y
+    Due,         // :[范围]due 日期
+    Sort,        // :sort [方式]
+    Filter,      // :filter [条件]
+    Write,       // :w [文件]
+    Quit,        // :q
+    WriteQuit,   // :wq [文件]
+    Set,         // :set 选项=值
+    NoHighlight, // :noh 清除搜索高亮
+}
+
+/// 命令名、最短缩写和是否接受行范围，补全时按此顺序列出
+const COMMANDS: [(&str, &str, CommandKind, bool); 12] = [
+    ("add", "a", CommandKind::Add, false),
+    ("done", "do", CommandKind::Done, true),
+    ("delete", "d", CommandKind::Delete, true),
+    ("tag", "t", CommandKind::Tag, true),
+    ("due", "du", CommandKind::Due, true),
+    ("sort", "so", CommandKind::Sort, false),
+    ("filter", "f", CommandKind::Filter, false),
+    ("write", "w", CommandKind::Write, false),
+    ("quit", "q", CommandKind::Quit, false),
+    ("wq", "wq", CommandKind::WriteQuit, false),
+    ("set", "se", CommandKind::Set, false),
+    ("nohlsearch", "noh", CommandKind::NoHighlight, false),
+];
+
+/// `:set` 支持的开关选项，`:set no选项` 关闭
+pub const SET_FLAGS: [&str; 3] = ["number", "future", "mine"];
+
+/// `:set` 支持的取值选项，用 `:set 选项=值` 设置
+pub const SET_VALUES: [&str; 1] = ["pomodoro"];
+
+/// `:sort` 可用的方式，另外还可以使用自定义字段名
+pub const SORT_NAMES: [&str; 3] = ["urgency", "manual", "added"];
+
+/// 行号，从 1 开始，对应当前列表中显示的顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineSpec {
+    Number(usize), // 3
+    Current,       // .
+    Last,          // $
+}
+
+/// 命令作用的行范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineRange {
+    All,                       // %
+    Lines(LineSpec, LineSpec), // 3,7 或单独的 3
+}
+
+/// 解析后的命令
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExCommand {
+    pub range: Option<LineRange>,
+    pub kind: CommandKind,
+    pub args: String,
+}
+
+impl LineSpec {
+    fn resolve(&self, current: Option<usize>, len: usize) -> Result<usize, String> {
+        match self {
+            Self::Number(n) if (1..=len).contains(n) => Ok(n - 1),
+            Self::Number(n) => Err(format!("行号 {n} 超出范围 1–{len}")),
+            Self::Current => current.ok_or_else(|| "没有选中的行".to_string()),
+            Self::Last if len > 0 => Ok(len - 1),
+            Self::Last => Err("列表为空".to_string()),
+        }
+    }
+}
+
+impl LineRange {
+    /// 换算为列表中的行下标（左闭右开），`current` 为当前选中的行
+    pub fn resolve(&self, current: Option<usize>, len: usize) -> Result<Range<usize>, String> {
+        match self {
+            Self::All => Ok(0..len),
+            Self::Lines(from, to) => {
+                let (from, to) = (from.resolve(current, len)?, to.resolve(current, len)?);
+                if from > to {
+                    return Err("行范围的起点在终点之后".to_string());
+                }
+                Ok(from..to + 1)
+            }
+        }
+    }
+}
+
+impl ExCommand {
+    /// 解析 `[范围]命令 [参数]`，例如 `3,7done`、`%tag +x`、`sort urgency`
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let input = input.trim();
+        let (range, rest) = parse_range(input)?;
+        let rest = rest.trim_start();
+
+        let name_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let (name, args) = rest.split_at(name_len);
+        if name.is_empty() {
+            return Err(format!("缺少命令：{input}"));
+        }
+
+        let &(full, _, kind, takes_range) = COMMANDS
+            .iter()
+            .find(|(full, short, ..)| full.starts_with(name) && name.len() >= short.len())
+            .ok_or_else(|| format!("未知命令 :{name}"))?;
+        if range.is_some() && !takes_range {
+            return Err(format!(":{full} 不接受行范围"));
+        }
+
+        Ok(Self {
+            range,
+            kind,
+            args: args.trim().to_string(),
+        })
+    }
+}
+
+/// 从开头解析行范围，返回范围和剩余的文本
+fn parse_range(input: &str) -> Result<(Option<LineRange>, &str), String> {
+    if let Some(rest) = input.strip_prefix('%') {
+        return Ok((Some(LineRange::All), rest));
+    }
+    let Some((from, rest)) = parse_line(input)? else {
+        return Ok((None, input));
+    };
+    match rest.strip_prefix(',') {
+        Some(rest) => match parse_line(rest)? {
+            Some((to, rest)) => Ok((Some(LineRange::Lines(from, to)), rest)),
+            None => Err("逗号后缺少行号".to_string()),
+        },
+        None => Ok((Some(LineRange::Lines(from, from)), rest)),
+    }
+}
+
+fn parse_line(input: &str) -> Result<Option<(LineSpec, &str)>, String> {
+    if let Some(rest) = input.strip_prefix('.') {
+        return Ok(Some((LineSpec::Current, rest)));
+    }
+    if let Some(rest) = input.strip_prefix('$') {
+        return Ok(Some((LineSpec::Last, rest)));
+    }
+    let digits = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    if digits == 0 {
+        return Ok(None);
+    }
+    let n = input[..digits]
+        .parse()
+        .map_err(|_| format!("无效的行号 {}", &input[..digits]))?;
+    Ok(Some((LineSpec::Number(n), &input[digits..])))
+}
+
+/// 命令行的 Tab 补全状态，再次按 Tab 在候选项之间循环
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    pub start: usize,            // 被替换部分在输入行中的起点
+    pub candidates: Vec<String>, // 候选项
+    pub selected: Option<usize>, // 当前填入的候选项
+}
+
+/// 候选项的公共前缀
+pub fn common_prefix(candidates: &[String]) -> &str {
+    let Some(first) = candidates.first() else {
+        return "";
+    };
+    let len = candidates.iter().skip(1).fold(first.len(), |len, other| {
+        first[..len]
+            .char_indices()
+            .zip(other.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(len.min(other.len()), |((at, _), _)| at)
+    });
+    &first[..len]
+}
+
+/// 补全输入行末尾的单词，返回被替换部分的起点和候选项
+///
+/// 命令名之后按命令补全参数：`sort` 补全排序方式，`set` 补全选项，
+/// `filter` 补全字段名，`tag` 补全已有标签
+pub fn complete_line(input: &str, fields: &[FieldDef], tags: &[String]) -> (usize, Vec<String>) {
+    let Ok((_, rest)) = parse_range(input) else {
+        return (input.len(), Vec::new());
+    };
+    let name_start = input.len() - rest.len();
+
+    // 还在输入命令名
+    if !rest.contains(char::is_whitespace) {
+        let candidates = COMMANDS
+            .iter()
+            .map(|(full, ..)| *full)
+            .filter(|full| full.starts_with(rest))
+            .map(str::to_string)
+            .collect();
+        return (name_start, candidates);
+    }
+
+    let word_start = input
+        .rfind(char::is_whitespace)
+        .map_or(input.len(), |at| at + 1);
+    let word = &input[word_start..];
+    let Ok(command) = ExCommand::parse(input) else {
+        return (word_start, Vec::new());
+    };
+
+    let options: Vec<String> = match command.kind {
+        CommandKind::Sort => SORT_NAMES
+            .iter()
+            .map(|name| name.to_string())
+            .chain(fields.iter().map(|def| def.name.clone()))
+            .collect(),
+        CommandKind::Set => SET_FLAGS
+            .iter()
+            .flat_map(|flag| [flag.to_string(), format!("no{flag}")])
+            .chain(SET_VALUES.iter().map(|option| format!("{option}=")))
+            .collect(),
+        CommandKind::Filter => fields.iter().map(|def| def.name.clone()).collect(),
+        CommandKind::Tag => tags
+            .iter()
+            .flat_map(|tag| [format!("+{tag}"), format!("-{tag}")])
+            .collect(),
+        _ => Vec::new(),
+    };
+    let candidates = options
+        .into_iter()
+        .filter(|option| option.starts_with(word))
+        .collect();
+    (word_start, candidates)
+}
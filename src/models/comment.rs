@@ -0,0 +1,20 @@
+use time::OffsetDateTime;
+
+use super::TodoTask;
+
+/// 追加在任务上的备注，与描述分开保存
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    pub at: OffsetDateTime,
+    pub text: String,
+}
+
+impl TodoTask {
+    /// 以当前时间追加一条备注
+    pub fn add_comment(&mut self, text: impl Into<String>) {
+        self.comments.push(Comment {
+            at: OffsetDateTime::now_utc(),
+            text: text.into(),
+        });
+    }
+}
@@ -0,0 +1,49 @@
+use time::{Date, Month, OffsetDateTime, Time};
+
+use super::parse_offset;
+
+/// 格式化日期时间，例如 2024-05-01 09:30
+pub fn format_datetime(datetime: OffsetDateTime) -> String {
+    format!(
+        "{}-{:02}-{:02} {:02}:{:02}",
+        datetime.year(),
+        datetime.month() as u8,
+        datetime.day(),
+        datetime.hour(),
+        datetime.minute()
+    )
+}
+
+/// 解析 `YYYY-MM-DD` 或 `YYYY-MM-DD HH:MM`
+///
+/// 只有日期时按当天 23:59 处理，这样截止日期当天仍算作"今日到期"而不是逾期
+pub fn parse_datetime(input: &str) -> Option<OffsetDateTime> {
+    let input = input.trim();
+    let (date_part, time_part) = match input.split_once([' ', 'T']) {
+        Some((date, time)) => (date, Some(time.trim())),
+        None => (input, None),
+    };
+
+    let mut fields = date_part.splitn(3, '-');
+    let year: i32 = fields.next()?.parse().ok()?;
+    let month: u8 = fields.next()?.parse().ok()?;
+    let day: u8 = fields.next()?.parse().ok()?;
+    let date = Date::from_calendar_date(year, Month::try_from(month).ok()?, day).ok()?;
+
+    let time = match time_part {
+        Some(time) => {
+            let (hour, minute) = time.split_once(':')?;
+            Time::from_hms(hour.parse().ok()?, minute.parse().ok()?, 0).ok()?
+        }
+        None => Time::from_hms(23, 59, 0).ok()?,
+    };
+
+    Some(date.with_time(time).assume_utc())
+}
+
+/// 解析截止日期：`YYYY-MM-DD [HH:MM]`，或相对 `now` 的 `3d`、`2h30m`
+///
+/// 相对时间超出可以表示的日期范围时视为无法解析
+pub fn parse_due(input: &str, now: OffsetDateTime) -> Option<OffsetDateTime> {
+    parse_datetime(input).or_else(|| parse_offset(input).and_then(|offset| now.checked_add(offset)))
+}
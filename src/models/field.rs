@@ -0,0 +1,213 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use super::{TodoTask, format_datetime, parse_datetime};
+
+/// 自定义字段的类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    #[default]
+    String, // 文本
+    Number, // 数字
+    Date,   // 日期
+    Enum,   // 从 options 中选择
+}
+
+/// 配置中声明的自定义字段
+///
+/// ```toml
+/// [[fields]]
+/// name = "points"
+/// label = "故事点"
+/// type = "number"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct FieldDef {
+    pub name: String,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(rename = "type", default)]
+    pub kind: FieldType,
+    #[serde(default)]
+    pub options: Vec<String>, // enum 类型的可选值，按声明顺序排序
+}
+
+/// 任务上某个自定义字段的值
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Text(String),
+    Number(f64),
+    Date(OffsetDateTime),
+    Choice(String),
+}
+
+impl FieldDef {
+    /// 显示名称，未设置 label 时使用 name
+    pub fn label(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.name)
+    }
+
+    /// 输入提示，说明字段接受的格式
+    pub fn hint(&self) -> String {
+        match self.kind {
+            FieldType::String => "文本".to_string(),
+            FieldType::Number => "数字".to_string(),
+            FieldType::Date => "YYYY-MM-DD [HH:MM]".to_string(),
+            FieldType::Enum => self.options.join(" / "),
+        }
+    }
+
+    /// 按字段类型解析输入
+    pub fn parse(&self, input: &str) -> Result<FieldValue, String> {
+        let input = input.trim();
+        match self.kind {
+            FieldType::String => Ok(FieldValue::Text(input.to_string())),
+            FieldType::Number => input
+                .parse()
+                .ok()
+                .filter(|n: &f64| n.is_finite())
+                .map(FieldValue::Number)
+                .ok_or_else(|| format!("{} 应为数字，而不是 `{input}`", self.label())),
+            FieldType::Date => parse_datetime(input).map(FieldValue::Date).ok_or_else(|| {
+                format!("{} 应为 YYYY-MM-DD [HH:MM]，而不是 `{input}`", self.label())
+            }),
+            FieldType::Enum => self
+                .options
+                .iter()
+                .find(|option| option.eq_ignore_ascii_case(input))
+                .map(|option| FieldValue::Choice(option.clone()))
+                .ok_or_else(|| format!("{} 可选值为 {}", self.label(), self.options.join(" / "))),
+        }
+    }
+
+    /// 比较同一字段的两个值；enum 按声明顺序比较
+    pub fn compare(&self, a: &FieldValue, b: &FieldValue) -> Ordering {
+        match (a, b) {
+            (FieldValue::Number(a), FieldValue::Number(b)) => a.total_cmp(b),
+            (FieldValue::Date(a), FieldValue::Date(b)) => a.cmp(b),
+            (FieldValue::Choice(a), FieldValue::Choice(b)) => {
+                let position = |value: &String| self.options.iter().position(|o| o == value);
+                position(a).cmp(&position(b))
+            }
+            (a, b) => a
+                .to_string()
+                .to_lowercase()
+                .cmp(&b.to_string().to_lowercase()),
+        }
+    }
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text(text) | Self::Choice(text) => write!(f, "{text}"),
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Date(date) => write!(f, "{}", format_datetime(*date)),
+        }
+    }
+}
+
+/// 自定义字段筛选条件
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldFilter {
+    pub field: String,
+    pub op: FilterOp,
+    pub value: Option<FieldValue>, // Has / Missing 时为 None
+    source: String,                // 用户输入的原文，用于显示
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterOp {
+    Has,          // name
+    Missing,      // !name
+    Equal,        // name=value
+    NotEqual,     // name!=value
+    Less,         // name<value
+    LessEqual,    // name<=value
+    Greater,      // name>value
+    GreaterEqual, // name>=value
+}
+
+impl FieldFilter {
+    /// 解析 `name=value`、`name>=3`、`name`（有值）或 `!name`（无值）
+    pub fn parse(input: &str, fields: &[FieldDef]) -> Result<Self, String> {
+        let input = input.trim();
+
+        // 按长度从长到短匹配，避免 `>=` 被当成 `>`
+        const OPERATORS: [(&str, FilterOp); 6] = [
+            ("!=", FilterOp::NotEqual),
+            ("<=", FilterOp::LessEqual),
+            (">=", FilterOp::GreaterEqual),
+            ("=", FilterOp::Equal),
+            ("<", FilterOp::Less),
+            (">", FilterOp::Greater),
+        ];
+        let split = OPERATORS
+            .iter()
+            .filter_map(|(symbol, op)| {
+                input
+                    .find(symbol)
+                    .map(|at| (at, &input[..at], *op, &input[at + symbol.len()..]))
+            })
+            .min_by_key(|(at, ..)| *at);
+
+        let (name, op, value) = match split {
+            Some((_, name, op, value)) => (name.trim(), op, Some(value.trim())),
+            None => match input.strip_prefix('!') {
+                Some(name) => (name.trim(), FilterOp::Missing, None),
+                None => (input, FilterOp::Has, None),
+            },
+        };
+
+        let def = fields
+            .iter()
+            .find(|def| def.name == name)
+            .ok_or_else(|| format!("未定义的字段 `{name}`"))?;
+        let value = value.map(|value| def.parse(value)).transpose()?;
+
+        Ok(Self {
+            field: def.name.clone(),
+            op,
+            value,
+            source: input.to_string(),
+        })
+    }
+
+    pub fn matches(&self, task: &TodoTask, fields: &[FieldDef]) -> bool {
+        let actual = task.fields.get(&self.field);
+        let (Some(actual), Some(expected)) = (actual, &self.value) else {
+            return match self.op {
+                FilterOp::Has => actual.is_some(),
+                FilterOp::Missing => actual.is_none(),
+                // 没有值的任务只满足 !=
+                FilterOp::NotEqual => true,
+                _ => false,
+            };
+        };
+        let Some(def) = fields.iter().find(|def| def.name == self.field) else {
+            return false;
+        };
+
+        let ordering = def.compare(actual, expected);
+        match self.op {
+            FilterOp::Has => true,
+            FilterOp::Missing => false,
+            FilterOp::Equal => ordering == Ordering::Equal,
+            FilterOp::NotEqual => ordering != Ordering::Equal,
+            FilterOp::Less => ordering == Ordering::Less,
+            FilterOp::LessEqual => ordering != Ordering::Greater,
+            FilterOp::Greater => ordering == Ordering::Greater,
+            FilterOp::GreaterEqual => ordering != Ordering::Less,
+        }
+    }
+}
+
+impl fmt::Display for FieldFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
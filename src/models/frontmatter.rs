@@ -0,0 +1,394 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::{self, Write};
+
+use time::OffsetDateTime;
+
+use super::{
+    Estimate, FieldDef, FieldValue, Priority, Tag, TaskLink, TaskStatus, TodoTask, format_datetime,
+    parse_datetime, parse_progress,
+};
+
+const DELIMITER: &str = "---";
+
+/// front matter 解析错误，`line` 从 1 开始
+#[derive(Debug, Clone)]
+pub struct FrontMatterError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl FrontMatterError {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for FrontMatterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "第 {} 行: {}", self.line, self.message)
+    }
+}
+
+/// 从文档中解析出的任务字段
+struct TaskDocument {
+    title: String,
+    done: bool,
+    priority: Option<Priority>,
+    assignee: Option<String>,
+    list: Option<String>,
+    contexts: BTreeSet<String>,
+    tags: HashSet<Tag>,
+    scheduled_date: Option<OffsetDateTime>,
+    due_date: Option<OffsetDateTime>,
+    estimate: Option<Estimate>,
+    progress: Option<u8>,
+    links: Vec<TaskLink>,
+    fields: BTreeMap<String, Option<FieldValue>>, // 文档中出现的自定义字段，None 表示清除
+    description: String,
+}
+
+impl TodoTask {
+    /// 把任务导出为带 front matter 的 Markdown 文档，供外部编辑器编辑
+    ///
+    /// `fields` 为配置中声明的自定义字段，未设置的字段也会输出空行方便填写
+    pub fn to_front_matter(&self, fields: &[FieldDef]) -> String {
+        self.write_document(fields, false)
+    }
+
+    /// `:w` 导出用的文档：在可编辑的字段之后附带 ID、手动顺序、创建者、备注和变更历史
+    ///
+    /// 这些字段只用于保存，再次在编辑器中打开时会被忽略
+    pub fn to_export(&self, fields: &[FieldDef]) -> String {
+        self.write_document(fields, true)
+    }
+
+    fn write_document(&self, fields: &[FieldDef], export: bool) -> String {
+        let mut tags: Vec<&str> = self.tags.iter().map(|tag| tag.name()).collect();
+        tags.sort_unstable();
+
+        let status = if self.status == TaskStatus::Completed {
+            "done"
+        } else {
+            "todo"
+        };
+        let scheduled = self.scheduled_date.map(format_datetime).unwrap_or_default();
+        let due = self.due_date.map(format_datetime).unwrap_or_default();
+        let estimate = self.estimate.map(|e| e.label()).unwrap_or_default();
+
+        let mut document = String::new();
+        let _ = writeln!(document, "{DELIMITER}");
+        let _ = writeln!(document, "title: {}", self.title);
+        let _ = writeln!(document, "status: {status}");
+        let _ = writeln!(
+            document,
+            "priority: {}",
+            self.priority.map_or("", |p| p.code())
+        );
+        let _ = writeln!(
+            document,
+            "assignee: {}",
+            self.assignee.as_deref().unwrap_or_default()
+        );
+        let _ = writeln!(
+            document,
+            "list: {}",
+            self.list.as_deref().unwrap_or_default()
+        );
+        let contexts: Vec<String> = self.contexts.iter().map(|c| format!("@{c}")).collect();
+        let _ = writeln!(document, "contexts: {}", contexts.join(", "));
+        let _ = writeln!(document, "tags: {}", tags.join(", "));
+        let _ = writeln!(document, "scheduled: {scheduled}");
+        let _ = writeln!(document, "due: {due}");
+        let _ = writeln!(document, "estimate: {estimate}");
+        let progress = self
+            .manual_progress
+            .map(|progress| progress.to_string())
+            .unwrap_or_default();
+        let _ = writeln!(document, "progress: {progress}");
+        for link in &self.links {
+            let _ = writeln!(document, "link: {link}");
+        }
+        for def in fields {
+            let value = self
+                .fields
+                .get(&def.name)
+                .map(|value| value.to_string())
+                .unwrap_or_default();
+            let _ = writeln!(document, "{}: {value}", def.name);
+        }
+        if export {
+            let _ = writeln!(document, "id: {}", self.id);
+            let _ = writeln!(document, "position: {}", self.position);
+            let _ = writeln!(document, "created: {}", format_datetime(self.created_at));
+            let _ = writeln!(
+                document,
+                "created_by: {}",
+                self.created_by.as_deref().unwrap_or_default()
+            );
+            // 备注和历史每条一行
+            for comment in &self.comments {
+                let text = comment.text.replace('\n', " ");
+                let _ = writeln!(document, "comment: {} {text}", format_datetime(comment.at));
+            }
+            for activity in &self.history {
+                let _ = writeln!(
+                    document,
+                    "history: {} {}",
+                    format_datetime(activity.at),
+                    activity.kind.describe()
+                );
+            }
+        }
+        let _ = writeln!(document, "{DELIMITER}");
+        let _ = writeln!(document, "{}", self.description);
+        document
+    }
+
+    /// 解析编辑后的文档并写回任务；解析失败时任务保持不变
+    pub fn apply_front_matter(
+        &mut self,
+        document: &str,
+        fields: &[FieldDef],
+    ) -> Result<(), FrontMatterError> {
+        let parsed = parse_document(document, fields)?;
+        let before = self.clone();
+
+        self.title = parsed.title;
+        self.priority = parsed.priority;
+        self.assignee = parsed.assignee;
+        self.list = parsed.list;
+        self.contexts = parsed.contexts;
+        self.tags = parsed.tags;
+        self.description = parsed.description;
+        self.scheduled_date = keep_precision(self.scheduled_date, parsed.scheduled_date);
+        self.due_date = keep_precision(self.due_date, parsed.due_date);
+        self.estimate = parsed.estimate;
+        self.manual_progress = parsed.progress;
+        self.links = parsed.links;
+        for (name, value) in parsed.fields {
+            match value {
+                Some(value) => self.fields.insert(name, value),
+                None => self.fields.remove(&name),
+            };
+        }
+
+        match (parsed.done, self.status == TaskStatus::Completed) {
+            (true, false) => self.complete(),
+            (false, true) => self.reopen(),
+            _ => {}
+        }
+        self.update_status();
+        self.record_changes(&before);
+        Ok(())
+    }
+}
+
+fn parse_document(document: &str, fields: &[FieldDef]) -> Result<TaskDocument, FrontMatterError> {
+    let mut lines = document.lines().enumerate();
+
+    match lines.next() {
+        Some((_, line)) if line.trim() == DELIMITER => {}
+        _ => return Err(FrontMatterError::new(1, "文档必须以 --- 开头")),
+    }
+
+    let mut title = None;
+    let mut done = false;
+    let mut priority = None;
+    let mut assignee = None;
+    let mut list = None;
+    let mut contexts = BTreeSet::new();
+    let mut tags = HashSet::new();
+    let mut scheduled_date = None;
+    let mut due_date = None;
+    let mut estimate = None;
+    let mut progress = None;
+    let mut links = Vec::new();
+    let mut field_values = BTreeMap::new();
+    let mut closed = false;
+
+    for (index, line) in lines.by_ref() {
+        let line_no = index + 1;
+        let line = line.trim();
+
+        if line == DELIMITER {
+            closed = true;
+            break;
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(':') else {
+            return Err(FrontMatterError::new(line_no, "应为 `键: 值` 格式"));
+        };
+        let value = value.trim();
+
+        match key.trim() {
+            "title" => title = Some(value.to_string()),
+            "status" => {
+                done = match value {
+                    "done" => true,
+                    "todo" => false,
+                    other => {
+                        return Err(FrontMatterError::new(
+                            line_no,
+                            format!("未知状态 `{other}`，可选 todo / done"),
+                        ));
+                    }
+                };
+            }
+            "priority" => {
+                priority = if value.is_empty() {
+                    None
+                } else {
+                    Some(Priority::parse(value).ok_or_else(|| {
+                        FrontMatterError::new(
+                            line_no,
+                            format!("未知优先级 `{value}`，可选 H / M / L"),
+                        )
+                    })?)
+                };
+            }
+            "assignee" => {
+                let handle = value.trim_start_matches('@');
+                assignee = (!handle.is_empty()).then(|| handle.to_string());
+            }
+            // 留空表示放回收件箱
+            "list" => list = (!value.is_empty()).then(|| value.to_string()),
+            "contexts" => {
+                contexts = value
+                    .split(',')
+                    .map(|context| context.trim().trim_start_matches('@'))
+                    .filter(|context| !context.is_empty())
+                    .map(str::to_string)
+                    .collect();
+            }
+            "tags" => {
+                tags = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .map(|tag| Tag::new(tag.trim_start_matches('#').to_string()))
+                    .collect();
+            }
+            "scheduled" => scheduled_date = parse_optional_date(value, line_no)?,
+            "due" => due_date = parse_optional_date(value, line_no)?,
+            "estimate" => {
+                estimate = if value.is_empty() {
+                    None
+                } else {
+                    Some(Estimate::parse(value).ok_or_else(|| {
+                        FrontMatterError::new(
+                            line_no,
+                            format!("无法解析预估用时 `{value}`，例如 90m、1h30m、4p"),
+                        )
+                    })?)
+                };
+            }
+            "progress" => {
+                progress = if value.is_empty() {
+                    None
+                } else {
+                    Some(parse_progress(value).ok_or_else(|| {
+                        FrontMatterError::new(line_no, format!("进度应为 0–100，而不是 `{value}`"))
+                    })?)
+                };
+            }
+            // 每个链接占一行
+            "link" => {
+                if value.is_empty() {
+                    continue;
+                }
+                links.push(TaskLink::parse(value).ok_or_else(|| {
+                    FrontMatterError::new(line_no, format!("无法识别的链接 `{value}`"))
+                })?);
+            }
+            // `:w` 导出时附带的只读字段
+            "id" | "position" | "created" | "created_by" | "comment" | "history" => {}
+            other => {
+                let Some(def) = fields.iter().find(|def| def.name == other) else {
+                    return Err(FrontMatterError::new(
+                        line_no,
+                        format!("未知字段 `{other}`"),
+                    ));
+                };
+                let value = if value.is_empty() {
+                    None
+                } else {
+                    Some(
+                        def.parse(value)
+                            .map_err(|e| FrontMatterError::new(line_no, e))?,
+                    )
+                };
+                field_values.insert(def.name.clone(), value);
+            }
+        }
+    }
+
+    if !closed {
+        return Err(FrontMatterError::new(
+            document.lines().count().max(1),
+            "缺少结束的 ---",
+        ));
+    }
+
+    let title = title
+        .filter(|title| !title.is_empty())
+        .ok_or_else(|| FrontMatterError::new(1, "标题不能为空"))?;
+
+    let description = lines
+        .map(|(_, line)| line)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_end()
+        .to_string();
+
+    Ok(TaskDocument {
+        title,
+        done,
+        priority,
+        assignee,
+        list,
+        contexts,
+        tags,
+        scheduled_date,
+        due_date,
+        estimate,
+        progress,
+        links,
+        fields: field_values,
+        description,
+    })
+}
+
+/// 文档中的日期只精确到分钟，显示相同时保留原来的时间，避免丢掉秒并记下没有发生的修改
+fn keep_precision(
+    original: Option<OffsetDateTime>,
+    edited: Option<OffsetDateTime>,
+) -> Option<OffsetDateTime> {
+    match (original, edited) {
+        (Some(original), Some(edited)) if format_datetime(original) == format_datetime(edited) => {
+            Some(original)
+        }
+        _ => edited,
+    }
+}
+
+/// 空值表示清除日期
+fn parse_optional_date(
+    value: &str,
+    line_no: usize,
+) -> Result<Option<OffsetDateTime>, FrontMatterError> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    parse_datetime(value).map(Some).ok_or_else(|| {
+        FrontMatterError::new(
+            line_no,
+            format!("无法解析日期 `{value}`，格式为 YYYY-MM-DD [HH:MM]"),
+        )
+    })
+}
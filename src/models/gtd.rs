@@ -0,0 +1,39 @@
+use super::TodoTask;
+
+/// 配置中没有 contexts 时使用的情境
+pub const DEFAULT_CONTEXTS: [&str; 3] = ["home", "office", "phone"];
+
+impl TodoTask {
+    /// 还没有归入清单的任务留在收件箱中
+    pub fn in_inbox(&self) -> bool {
+        self.list.is_none()
+    }
+
+    /// 添加或移除情境，返回切换后任务是否带有该情境
+    pub fn toggle_context(&mut self, context: &str) -> bool {
+        if self.contexts.remove(context) {
+            false
+        } else {
+            self.contexts.insert(context.to_string());
+            true
+        }
+    }
+}
+
+/// 取出快速记录文本中的 `@情境`，只识别 `known` 中的情境，其余文字作为标题
+///
+/// 例如 `打电话给房东 @phone` → (`打电话给房东`, [`phone`])
+pub fn extract_contexts(input: &str, known: &[String]) -> (String, Vec<String>) {
+    let mut contexts = Vec::new();
+    let mut words = Vec::new();
+    for word in input.split_whitespace() {
+        match word
+            .strip_prefix('@')
+            .filter(|name| known.iter().any(|c| c == name))
+        {
+            Some(name) => contexts.push(name.to_string()),
+            None => words.push(word),
+        }
+    }
+    (words.join(" "), contexts)
+}
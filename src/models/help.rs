@@ -0,0 +1,83 @@
+use std::cell::Cell;
+
+use super::{TextInput, match_ranges};
+
+/// 帮助浮层自身的按键
+pub const HELP_KEYS: [(&str, &str); 7] = [
+    ("j/k ↓/↑", "滚动"),
+    ("Ctrl-D/Ctrl-U", "向下/向上半页"),
+    ("g/G", "开头/结尾"),
+    ("/", "搜索"),
+    ("Esc", "清除搜索/关闭"),
+    ("q/?", "关闭"),
+    ("F1", "在任何地方打开帮助"),
+];
+
+/// 详情面板获得焦点时的按键
+pub const DETAILS_KEYS: [(&str, &str); 4] = [
+    ("j/k", "选择"),
+    ("Space", "勾选"),
+    ("c", "备注"),
+    ("Esc", "返回"),
+];
+
+/// 帮助中的一组按键，例如某个模式或某个窗口
+#[derive(Debug, Clone, PartialEq)]
+pub struct HelpSection {
+    pub title: String,
+    pub entries: Vec<(String, String)>, // 按键和说明
+}
+
+impl HelpSection {
+    pub fn new<K: Into<String>, D: Into<String>>(
+        title: impl Into<String>,
+        entries: impl IntoIterator<Item = (K, D)>,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            entries: entries
+                .into_iter()
+                .map(|(keys, description)| (keys.into(), description.into()))
+                .collect(),
+        }
+    }
+
+    /// 只保留按键或说明与 `query` 匹配的条目，匹配规则与搜索任务相同；没有条目时返回 None
+    pub fn filter(mut self, query: &str) -> Option<Self> {
+        if query.is_empty() {
+            return Some(self);
+        }
+        self.entries.retain(|(keys, description)| {
+            !match_ranges(keys, query).is_empty() || !match_ranges(description, query).is_empty()
+        });
+        (!self.entries.is_empty()).then_some(self)
+    }
+}
+
+/// 帮助浮层的状态：列出当前模式和窗口的所有按键，可以滚动和搜索
+#[derive(Debug, Clone, Default)]
+pub struct HelpState {
+    pub scroll: usize,       // 顶部显示的行
+    pub query: TextInput,    // 搜索词，只显示匹配的按键
+    pub searching: bool,     // 正在输入搜索词
+    max_scroll: Cell<usize>, // 上次渲染时能滚动到的最后一行
+}
+
+impl HelpState {
+    /// 按可见高度限制滚动位置，返回顶部显示的行；渲染时调用
+    pub fn scroll_for(&self, lines: usize, height: usize) -> usize {
+        let max = lines.saturating_sub(height);
+        self.max_scroll.set(max);
+        self.scroll.min(max)
+    }
+
+    /// 向下（正数）或向上（负数）滚动，停在两端
+    pub fn scroll_by(&mut self, delta: isize) {
+        let max = self.max_scroll.get();
+        self.scroll = self.scroll.min(max).saturating_add_signed(delta).min(max);
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = self.max_scroll.get();
+    }
+}
@@ -0,0 +1,155 @@
+use time::OffsetDateTime;
+
+use super::{TodoTask, format_datetime};
+
+/// 任务历史中的一条记录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Activity {
+    pub at: OffsetDateTime,
+    pub kind: ActivityKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActivityKind {
+    Created,   // 创建
+    Completed, // 完成
+    Reopened,  // 重新打开
+    Renamed {
+        from: String,
+        to: String,
+    },
+    DueChanged {
+        from: Option<OffsetDateTime>,
+        to: Option<OffsetDateTime>,
+    },
+    Rescheduled {
+        from: Option<OffsetDateTime>,
+        to: Option<OffsetDateTime>,
+    },
+    TagAdded(String),
+    TagRemoved(String),
+    Assigned {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    Filed {
+        from: Option<String>,
+        to: Option<String>,
+    },
+}
+
+impl ActivityKind {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Self::Created => "✨",
+            Self::Completed => "✅",
+            Self::Reopened => "↩",
+            Self::Renamed { .. } => "✏",
+            Self::DueChanged { .. } => "📅",
+            Self::Rescheduled { .. } => "⏰",
+            Self::TagAdded(_) | Self::TagRemoved(_) => "🏷",
+            Self::Assigned { .. } => "👤",
+            Self::Filed { .. } => "📁",
+        }
+    }
+
+    /// 面向用户的描述，例如"截止日期 10-20 09:00 → 10-24 09:00"
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Created => "创建".to_string(),
+            Self::Completed => "完成".to_string(),
+            Self::Reopened => "重新打开".to_string(),
+            Self::Renamed { from, to } => format!("标题 「{from}」 → 「{to}」"),
+            Self::DueChanged { from, to } => {
+                format!("截止日期 {} → {}", date_label(*from), date_label(*to))
+            }
+            Self::Rescheduled { from, to } => {
+                format!("开始日期 {} → {}", date_label(*from), date_label(*to))
+            }
+            Self::TagAdded(tag) => format!("添加标签 #{tag}"),
+            Self::TagRemoved(tag) => format!("移除标签 #{tag}"),
+            Self::Filed { from, to } => format!(
+                "清单 {} → {}",
+                from.as_deref().unwrap_or("收件箱"),
+                to.as_deref().unwrap_or("收件箱")
+            ),
+            Self::Assigned { from, to } => format!(
+                "负责人 {} → {}",
+                from.as_deref()
+                    .map_or("无".to_string(), |h| format!("@{h}")),
+                to.as_deref().map_or("无".to_string(), |h| format!("@{h}"))
+            ),
+        }
+    }
+}
+
+fn date_label(date: Option<OffsetDateTime>) -> String {
+    date.map(format_datetime)
+        .unwrap_or_else(|| "无".to_string())
+}
+
+impl TodoTask {
+    /// 以当前时间记录一条历史
+    pub fn record(&mut self, kind: ActivityKind) {
+        self.history.push(Activity {
+            at: OffsetDateTime::now_utc(),
+            kind,
+        });
+    }
+
+    /// 对比修改前的任务，记录标题、截止日期、开始日期、负责人、清单和标签的变化
+    ///
+    /// 完成与重新打开由 `complete` / `reopen` 自己记录
+    pub fn record_changes(&mut self, before: &TodoTask) {
+        if self.title != before.title {
+            self.record(ActivityKind::Renamed {
+                from: before.title.clone(),
+                to: self.title.clone(),
+            });
+        }
+        if self.due_date != before.due_date {
+            self.record(ActivityKind::DueChanged {
+                from: before.due_date,
+                to: self.due_date,
+            });
+        }
+        if self.scheduled_date != before.scheduled_date {
+            self.record(ActivityKind::Rescheduled {
+                from: before.scheduled_date,
+                to: self.scheduled_date,
+            });
+        }
+
+        if self.assignee != before.assignee {
+            self.record(ActivityKind::Assigned {
+                from: before.assignee.clone(),
+                to: self.assignee.clone(),
+            });
+        }
+        if self.list != before.list {
+            self.record(ActivityKind::Filed {
+                from: before.list.clone(),
+                to: self.list.clone(),
+            });
+        }
+
+        let mut added: Vec<String> = self
+            .tags
+            .difference(&before.tags)
+            .map(|tag| tag.name().to_string())
+            .collect();
+        let mut removed: Vec<String> = before
+            .tags
+            .difference(&self.tags)
+            .map(|tag| tag.name().to_string())
+            .collect();
+        added.sort_unstable();
+        removed.sort_unstable();
+        for tag in added {
+            self.record(ActivityKind::TagAdded(tag));
+        }
+        for tag in removed {
+            self.record(ActivityKind::TagRemoved(tag));
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use std::cell::Cell;
+use std::ops::Range;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// 可复用的文本输入框：光标按字素簇移动、按显示宽度换算列，支持选区、横向滚动和多行
+///
+/// `cursor` 和 `anchor` 都是字节下标，始终落在字素簇边界上
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextInput {
+    text: String,
+    cursor: usize,
+    anchor: Option<usize>,        // 选区的另一端，与光标之间的文本为选中内容
+    multiline: bool,              // 是否允许 Alt-Enter / Ctrl-J 换行
+    scroll: Cell<(usize, usize)>, // 上次渲染时左上角的行号和显示列
+    killed: Option<String>,       // 最近一次按单词或整行删除的文本
+    copied: Option<String>,       // 最近一次用 Alt-W 复制的文本
+}
+
+/// 输入框的编辑按键，用于帮助；Ctrl-Y 由调用方处理，粘贴最近删除或复制的文本
+pub const TEXT_INPUT_KEYS: [(&str, &str); 9] = [
+    ("←/→", "移动"),
+    ("Ctrl-←/→ Alt-B/F", "按词移动"),
+    ("Home/End Ctrl-A/E", "行首/行尾"),
+    ("Shift-方向键", "选择"),
+    ("Ctrl-W Alt-Backspace", "删除前一个词"),
+    ("Alt-D", "删除后一个词"),
+    ("Ctrl-U/K", "删除到行首/行尾"),
+    ("Alt-W", "复制选中内容"),
+    ("Ctrl-Y", "粘贴"),
+];
+
+/// 单词移动时的字符类别，连续的同类字素构成一个词
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Space,
+    Word,        // 字母、数字和下划线
+    Wide,        // 中日韩等宽字符，中文没有空格分词，连续的汉字算一个词
+    Punctuation, // 其余符号
+}
+
+impl CharClass {
+    fn of(grapheme: &str) -> Self {
+        let c = grapheme.chars().next().unwrap_or(' ');
+        if c.is_whitespace() {
+            Self::Space
+        } else if !c.is_alphanumeric() && c != '_' {
+            Self::Punctuation
+        } else if grapheme.width() > 1 {
+            Self::Wide
+        } else {
+            Self::Word
+        }
+    }
+}
+
+impl TextInput {
+    /// 单行输入框，光标放在末尾
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        Self {
+            cursor: text.len(),
+            text,
+            ..Self::default()
+        }
+    }
+
+    /// 多行输入框
+    pub fn multiline(text: impl Into<String>) -> Self {
+        Self {
+            multiline: true,
+            ..Self::new(text)
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// 替换全部内容，光标移到末尾
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.cursor = self.text.len();
+        self.anchor = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.set_text(String::new());
+    }
+
+    /// 取出内容并清空输入框
+    pub fn take(&mut self) -> String {
+        self.cursor = 0;
+        self.anchor = None;
+        std::mem::take(&mut self.text)
+    }
+
+    /// 选中内容的字节范围，没有选区时为 None
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.anchor.filter(|anchor| *anchor != self.cursor)?;
+        Some(anchor.min(self.cursor)..anchor.max(self.cursor))
+    }
+
+    /// 光标所在的行号和显示列，宽字符占两列
+    pub fn cursor_position(&self) -> (usize, usize) {
+        let before = &self.text[..self.cursor];
+        let row = before.matches('\n').count();
+        (row, before[self.line_start()..].width())
+    }
+
+    /// 按可见区域的大小滚动到光标可见的位置，返回左上角的行号和显示列
+    pub fn scroll_to_cursor(&self, width: usize, height: usize) -> (usize, usize) {
+        let (row, col) = self.cursor_position();
+        let (top, left) = self.scroll.get();
+        // 光标在行尾时还要多占一列
+        let top = top.min(row).max((row + 1).saturating_sub(height));
+        let left = left.min(col).max((col + 1).saturating_sub(width));
+        self.scroll.set((top, left));
+        (top, left)
+    }
+
+    /// 在光标处粘贴文本，单行输入框中的换行替换为空格
+    pub fn paste(&mut self, text: &str) {
+        if self.multiline {
+            self.insert(text);
+        } else {
+            self.insert(&text.replace('\n', " "));
+        }
+    }
+
+    /// 取出最近删除的文本，由调用方存入小删除寄存器
+    pub fn take_killed(&mut self) -> Option<String> {
+        self.killed.take()
+    }
+
+    /// 取出最近复制的文本，由调用方存入无名寄存器
+    pub fn take_copied(&mut self) -> Option<String> {
+        self.copied.take()
+    }
+
+    /// 处理编辑按键，返回 false 表示输入框不处理这个按键
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        match key.code {
+            KeyCode::Char('a') if ctrl => self.move_to(self.line_start(), shift),
+            KeyCode::Char('e') if ctrl => self.move_to(self.line_end(), shift),
+            KeyCode::Char('u') if ctrl => self.kill_to(self.line_start()),
+            KeyCode::Char('k') if ctrl => self.kill_to(self.line_end()),
+            KeyCode::Char('w') if ctrl => self.kill_to(self.word_start()),
+            KeyCode::Char('j') if ctrl && self.multiline => self.insert("\n"),
+            KeyCode::Char('b') if alt => self.move_to(self.word_start(), shift),
+            KeyCode::Char('f') if alt => self.move_to(self.word_end(), shift),
+            KeyCode::Char('d') if alt => self.kill_to(self.word_end()),
+            KeyCode::Char('w') if alt => {
+                self.copied = self.selection().map(|range| self.text[range].to_string());
+                self.anchor = None;
+            }
+            KeyCode::Char(_) if ctrl || alt => return false,
+            KeyCode::Char(c) => self.insert(c.encode_utf8(&mut [0; 4])),
+            KeyCode::Enter if self.multiline && (alt || shift) => self.insert("\n"),
+            KeyCode::Backspace if ctrl || alt => self.kill_to(self.word_start()),
+            KeyCode::Backspace => self.delete_to(self.previous_boundary()),
+            KeyCode::Delete => self.delete_to(self.next_boundary()),
+            KeyCode::Left if ctrl || alt => self.move_to(self.word_start(), shift),
+            KeyCode::Right if ctrl || alt => self.move_to(self.word_end(), shift),
+            KeyCode::Left => self.move_to(self.previous_boundary(), shift),
+            KeyCode::Right => self.move_to(self.next_boundary(), shift),
+            KeyCode::Home => self.move_to(self.line_start(), shift),
+            KeyCode::End => self.move_to(self.line_end(), shift),
+            KeyCode::Up if self.multiline => self.move_vertically(false, shift),
+            KeyCode::Down if self.multiline => self.move_vertically(true, shift),
+            _ => return false,
+        }
+        true
+    }
+
+    /// 插入文本，有选区时替换选中的内容
+    fn insert(&mut self, s: &str) {
+        let range = self.selection().unwrap_or(self.cursor..self.cursor);
+        self.text.replace_range(range.clone(), s);
+        self.cursor = range.start + s.len();
+        self.anchor = None;
+    }
+
+    /// 删除光标到 `target` 之间的文本，有选区时只删除选中的内容
+    fn delete_to(&mut self, target: usize) {
+        let range = self
+            .selection()
+            .unwrap_or(self.cursor.min(target)..self.cursor.max(target));
+        self.text.replace_range(range.clone(), "");
+        self.cursor = range.start;
+        self.anchor = None;
+    }
+
+    /// 删除文本并记下删除的内容，以便存入寄存器
+    fn kill_to(&mut self, target: usize) {
+        let range = self
+            .selection()
+            .unwrap_or(self.cursor.min(target)..self.cursor.max(target));
+        if !range.is_empty() {
+            self.killed = Some(self.text[range].to_string());
+        }
+        self.delete_to(target);
+    }
+
+    /// 移动光标，`extend` 为 true 时扩展选区，否则取消选区
+    fn move_to(&mut self, target: usize, extend: bool) {
+        self.anchor = if extend {
+            self.anchor.or(Some(self.cursor))
+        } else {
+            None
+        };
+        self.cursor = target;
+    }
+
+    /// 移到上一行或下一行的相同显示列，已在首行或末行时移到行首或行尾
+    fn move_vertically(&mut self, down: bool, extend: bool) {
+        let (_, col) = self.cursor_position();
+        let line_start = if down {
+            match self.text[self.cursor..].find('\n') {
+                Some(i) => self.cursor + i + 1,
+                None => return self.move_to(self.text.len(), extend),
+            }
+        } else {
+            match self.line_start().checked_sub(1) {
+                Some(end) => self.text[..end].rfind('\n').map_or(0, |i| i + 1),
+                None => return self.move_to(0, extend),
+            }
+        };
+        let line = self.text[line_start..]
+            .split('\n')
+            .next()
+            .unwrap_or_default();
+        let mut target = line_start;
+        let mut width = 0;
+        for grapheme in line.graphemes(true) {
+            width += grapheme.width();
+            if width > col {
+                break;
+            }
+            target += grapheme.len();
+        }
+        self.move_to(target, extend);
+    }
+
+    fn line_start(&self) -> usize {
+        self.text[..self.cursor].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    fn line_end(&self) -> usize {
+        self.text[self.cursor..]
+            .find('\n')
+            .map_or(self.text.len(), |i| self.cursor + i)
+    }
+
+    fn previous_boundary(&self) -> usize {
+        self.text[..self.cursor]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn next_boundary(&self) -> usize {
+        self.text[self.cursor..]
+            .graphemes(true)
+            .next()
+            .map_or(self.cursor, |grapheme| self.cursor + grapheme.len())
+    }
+
+    /// 光标前一个词的开头，会先跳过空白
+    fn word_start(&self) -> usize {
+        let mut graphemes = self.text[..self.cursor]
+            .grapheme_indices(true)
+            .rev()
+            .skip_while(|(_, grapheme)| CharClass::of(grapheme) == CharClass::Space)
+            .peekable();
+        let Some(class) = graphemes
+            .peek()
+            .map(|(_, grapheme)| CharClass::of(grapheme))
+        else {
+            return 0;
+        };
+        graphemes
+            .take_while(|(_, grapheme)| CharClass::of(grapheme) == class)
+            .last()
+            .map_or(0, |(i, _)| i)
+    }
+
+    /// 光标后一个词的结尾，会先跳过空白
+    fn word_end(&self) -> usize {
+        let mut graphemes = self.text[self.cursor..]
+            .grapheme_indices(true)
+            .skip_while(|(_, grapheme)| CharClass::of(grapheme) == CharClass::Space)
+            .peekable();
+        let Some(class) = graphemes
+            .peek()
+            .map(|(_, grapheme)| CharClass::of(grapheme))
+        else {
+            return self.text.len();
+        };
+        graphemes
+            .find(|(_, grapheme)| CharClass::of(grapheme) != class)
+            .map_or(self.text.len(), |(i, _)| self.cursor + i)
+    }
+}
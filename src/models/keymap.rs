@@ -0,0 +1,760 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+use super::{AppMode, Registers};
+
+/// 可以绑定按键的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Help,
+    ToggleFocus,
+    Down,
+    Up,
+    GotoTop,
+    GotoBottom,
+    HalfPageDown,
+    HalfPageUp,
+    VisualMode,
+    CommandMode,
+    SearchMode,
+    SearchNext,
+    SearchPrev,
+    NewTask,
+    FromTemplate,
+    Pomodoro,
+    FinishPomodoro,
+    Settings,
+    EditDescription,
+    EditTask,
+    ToggleTracking,
+    Estimate,
+    Defer,
+    MoveUp,
+    MoveDown,
+    MoveTop,
+    MoveBottom,
+    Duplicate,
+    BulkDates,
+    AddLink,
+    OpenLinks,
+    ToggleFuture,
+    Progress,
+    CyclePriority,
+    CycleSort,
+    Assign,
+    ToggleMine,
+    Capture,
+    ProcessInbox,
+    Filter,
+    Delete,
+    Yank,
+    Put,
+    Repeat,
+    RecordMacro,
+    ReplayMacro,
+    Complete,
+    Tag,
+    SetDue,
+    MoveToList,
+    ExitVisual,
+    Submit,
+    Cancel,
+    CompleteNext,
+    CompletePrev,
+    HistoryPrev,
+    HistoryNext,
+}
+
+/// 动作在按键映射文件中的名称、说明，以及是否在状态栏中提示
+const ACTIONS: [(Action, &str, &str, bool); 58] = [
+    (Action::Quit, "quit", "退出", false),
+    (Action::Help, "help", "帮助", true),
+    (Action::ToggleFocus, "toggle_focus", "详情", true),
+    (Action::Down, "down", "下移", false),
+    (Action::Up, "up", "上移", false),
+    (Action::GotoTop, "goto_top", "第一行", false),
+    (Action::GotoBottom, "goto_bottom", "最后一行", false),
+    (Action::HalfPageDown, "half_page_down", "向下半页", false),
+    (Action::HalfPageUp, "half_page_up", "向上半页", false),
+    (Action::VisualMode, "visual_mode", "可视", true),
+    (Action::CommandMode, "command_mode", "命令", true),
+    (Action::SearchMode, "search", "搜索", true),
+    (Action::SearchNext, "search_next", "下一个匹配", false),
+    (Action::SearchPrev, "search_prev", "上一个匹配", false),
+    (Action::NewTask, "new_task", "新建任务", false),
+    (Action::FromTemplate, "from_template", "从模板新建", false),
+    (Action::Pomodoro, "pomodoro", "番茄钟", false),
+    (
+        Action::FinishPomodoro,
+        "finish_pomodoro",
+        "结束番茄钟",
+        false,
+    ),
+    (Action::Settings, "settings", "设置", false),
+    (
+        Action::EditDescription,
+        "edit_description",
+        "编辑描述",
+        true,
+    ),
+    (Action::EditTask, "edit_task", "编辑任务", true),
+    (Action::ToggleTracking, "toggle_tracking", "计时", true),
+    (Action::Estimate, "estimate", "预估", true),
+    (Action::Defer, "defer", "推迟", true),
+    (Action::MoveUp, "move_up", "上移任务", false),
+    (Action::MoveDown, "move_down", "下移任务", false),
+    (Action::MoveTop, "move_top", "移到最前", false),
+    (Action::MoveBottom, "move_bottom", "移到最后", false),
+    (Action::Duplicate, "duplicate", "复制为新任务", false),
+    (Action::BulkDates, "bulk_dates", "批量调整日期", false),
+    (Action::AddLink, "add_link", "加链接", true),
+    (Action::OpenLinks, "open_links", "打开链接", true),
+    (Action::ToggleFuture, "toggle_future", "显示未开始", false),
+    (Action::Progress, "progress", "进度", false),
+    (Action::CyclePriority, "cycle_priority", "优先级", false),
+    (Action::CycleSort, "cycle_sort", "排序", true),
+    (Action::Assign, "assign", "负责人", true),
+    (Action::ToggleMine, "toggle_mine", "我的", true),
+    (Action::Capture, "capture", "记录", true),
+    (Action::ProcessInbox, "process_inbox", "收件箱", true),
+    (Action::Filter, "filter", "筛选", true),
+    (Action::Delete, "delete", "删除", true),
+    (Action::Yank, "yank", "复制", true),
+    (Action::Put, "put", "粘贴", true),
+    (Action::Repeat, "repeat", "重复修改", false),
+    (Action::RecordMacro, "record_macro", "录制宏", false),
+    (Action::ReplayMacro, "replay_macro", "执行宏", false),
+    (Action::Complete, "complete", "完成", true),
+    (Action::Tag, "tag", "标签", true),
+    (Action::SetDue, "set_due", "截止日期", true),
+    (Action::MoveToList, "move_to_list", "移到清单", true),
+    (Action::ExitVisual, "exit_visual", "返回", true),
+    (Action::Submit, "submit", "确认", true),
+    (Action::Cancel, "cancel", "取消", true),
+    (Action::CompleteNext, "complete_next", "补全", true),
+    (Action::CompletePrev, "complete_prev", "上一个补全", false),
+    (Action::HistoryPrev, "history_prev", "上一条历史", true),
+    (Action::HistoryNext, "history_next", "下一条历史", false),
+];
+
+/// 普通模式的默认按键
+const NORMAL_KEYS: [(&str, Action); 51] = [
+    ("q", Action::Quit),
+    ("?", Action::Help),
+    ("<Tab>", Action::ToggleFocus),
+    ("j", Action::Down),
+    ("<Down>", Action::Down),
+    ("k", Action::Up),
+    ("<Up>", Action::Up),
+    ("gg", Action::GotoTop),
+    ("G", Action::GotoBottom),
+    ("<C-d>", Action::HalfPageDown),
+    ("<C-u>", Action::HalfPageUp),
+    ("v", Action::VisualMode),
+    (":", Action::CommandMode),
+    ("/", Action::SearchMode),
+    ("n", Action::SearchNext),
+    ("N", Action::SearchPrev),
+    ("a", Action::NewTask),
+    ("A", Action::FromTemplate),
+    ("w", Action::Pomodoro),
+    ("P", Action::FinishPomodoro),
+    ("o", Action::Settings),
+    ("e", Action::EditDescription),
+    ("E", Action::EditTask),
+    ("t", Action::ToggleTracking),
+    ("T", Action::Estimate),
+    ("z", Action::Defer),
+    ("K", Action::MoveUp),
+    ("J", Action::MoveDown),
+    ("{", Action::MoveTop),
+    ("}", Action::MoveBottom),
+    ("D", Action::Duplicate),
+    ("B", Action::BulkDates),
+    ("L", Action::AddLink),
+    ("O", Action::OpenLinks),
+    ("F", Action::ToggleFuture),
+    ("%", Action::Progress),
+    ("!", Action::CyclePriority),
+    ("s", Action::CycleSort),
+    ("@", Action::Assign),
+    ("m", Action::ToggleMine),
+    ("i", Action::Capture),
+    ("I", Action::ProcessInbox),
+    ("f", Action::Filter),
+    ("dd", Action::Delete),
+    ("yy", Action::Yank),
+    ("p", Action::Put),
+    (".", Action::Repeat),
+    ("gq", Action::RecordMacro),
+    ("g@", Action::ReplayMacro),
+    ("<PageDown>", Action::HalfPageDown),
+    ("<PageUp>", Action::HalfPageUp),
+];
+
+/// 可视模式的默认按键
+const VISUAL_KEYS: [(&str, Action); 22] = [
+    ("j", Action::Down),
+    ("<Down>", Action::Down),
+    ("k", Action::Up),
+    ("<Up>", Action::Up),
+    ("gg", Action::GotoTop),
+    ("G", Action::GotoBottom),
+    ("x", Action::Delete),
+    ("d", Action::Delete),
+    ("y", Action::Yank),
+    ("c", Action::Complete),
+    ("t", Action::Tag),
+    ("D", Action::SetDue),
+    ("l", Action::MoveToList),
+    ("B", Action::BulkDates),
+    ("K", Action::MoveUp),
+    ("J", Action::MoveDown),
+    ("{", Action::MoveTop),
+    ("}", Action::MoveBottom),
+    ("<Esc>", Action::ExitVisual),
+    ("v", Action::ExitVisual),
+    (":", Action::CommandMode),
+    ("?", Action::Help),
+];
+
+/// 插入模式的默认按键，确认和取消交给正在输入的窗口
+const INSERT_KEYS: [(&str, Action); 3] = [
+    ("<CR>", Action::Submit),
+    ("<Esc>", Action::Cancel),
+    ("<F1>", Action::Help),
+];
+
+/// 命令模式的默认按键
+const COMMAND_KEYS: [(&str, Action); 7] = [
+    ("<CR>", Action::Submit),
+    ("<Tab>", Action::CompleteNext),
+    ("<S-Tab>", Action::CompletePrev),
+    ("<Up>", Action::HistoryPrev),
+    ("<Down>", Action::HistoryNext),
+    ("<Esc>", Action::Cancel),
+    ("<F1>", Action::Help),
+];
+
+/// 搜索模式的默认按键
+const SEARCH_KEYS: [(&str, Action); 3] = [
+    ("<CR>", Action::Submit),
+    ("<Esc>", Action::Cancel),
+    ("<F1>", Action::Help),
+];
+
+impl Action {
+    fn entry(&self) -> &'static (Action, &'static str, &'static str, bool) {
+        ACTIONS
+            .iter()
+            .find(|(action, ..)| action == self)
+            .expect("每个动作都在 ACTIONS 中登记")
+    }
+
+    /// 在按键映射文件中使用的名称
+    pub fn name(&self) -> &'static str {
+        self.entry().1
+    }
+
+    pub fn description(&self) -> &'static str {
+        self.entry().2
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        ACTIONS
+            .iter()
+            .find(|(_, n, ..)| *n == name)
+            .map(|(action, ..)| *action)
+    }
+
+    /// 需要再输入一个寄存器名作为参数的动作，例如 `gqa`、`g@a`
+    fn takes_register(&self) -> bool {
+        matches!(self, Self::RecordMacro | Self::ReplayMacro)
+    }
+
+    /// 直接修改任务的动作，可以用 `.` 重复；需要输入的修改见 [`super::Change`]
+    pub fn is_change(&self) -> bool {
+        matches!(
+            self,
+            Self::Delete
+                | Self::Put
+                | Self::Duplicate
+                | Self::CyclePriority
+                | Self::MoveUp
+                | Self::MoveDown
+        )
+    }
+}
+
+/// 一次按键，字母键的大小写已经体现在字符中，不再记录 Shift
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyPress {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyPress {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let code = match code {
+            // Ctrl 组合的字母不区分大小写
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => {
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            other => other,
+        };
+        if matches!(code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Self { code, modifiers }
+    }
+}
+
+impl From<KeyEvent> for KeyPress {
+    fn from(event: KeyEvent) -> Self {
+        Self::new(event.code, event.modifiers)
+    }
+}
+
+/// 按 Vim 的写法显示，例如 `a`、`<C-d>`、`<Tab>`
+impl fmt::Display for KeyPress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char('<') => "lt".to_string(),
+            KeyCode::Char(c) if self.modifiers.is_empty() => return write!(f, "{c}"),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::BackTab => "S-Tab".to_string(),
+            KeyCode::Backspace => "BS".to_string(),
+            KeyCode::Delete => "Del".to_string(),
+            KeyCode::Up => "Up".to_string(),
+            KeyCode::Down => "Down".to_string(),
+            KeyCode::Left => "Left".to_string(),
+            KeyCode::Right => "Right".to_string(),
+            KeyCode::Home => "Home".to_string(),
+            KeyCode::End => "End".to_string(),
+            KeyCode::PageUp => "PageUp".to_string(),
+            KeyCode::PageDown => "PageDown".to_string(),
+            KeyCode::F(n) => format!("F{n}"),
+            other => format!("{other:?}"),
+        };
+        let mut prefix = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            prefix.push_str("C-");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            prefix.push_str("A-");
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            prefix.push_str("S-");
+        }
+        write!(f, "<{prefix}{name}>")
+    }
+}
+
+/// 按键序列的显示，例如 `gg`、`<C-w>j`
+pub fn format_keys(keys: &[KeyPress]) -> String {
+    keys.iter().map(|key| key.to_string()).collect()
+}
+
+/// 解析 Vim 写法的按键序列：普通字符逐个是一次按键，`<...>` 中是特殊键或组合键，
+/// 修饰键写作 `C-`（Ctrl）、`A-`（Alt）、`S-`（Shift）
+pub fn parse_keys(input: &str) -> Result<Vec<KeyPress>, String> {
+    let mut keys = Vec::new();
+    let mut rest = input;
+    while let Some(c) = rest.chars().next() {
+        if c == '<'
+            && let Some(end) = rest.find('>').filter(|&end| end > 1)
+        {
+            keys.push(parse_special(&rest[1..end])?);
+            rest = &rest[end + 1..];
+        } else {
+            keys.push(KeyPress::new(KeyCode::Char(c), KeyModifiers::NONE));
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    if keys.is_empty() {
+        return Err("按键不能为空".to_string());
+    }
+    Ok(keys)
+}
+
+/// 解析 `<>` 中的内容，例如 `C-d`、`Esc`、`A-S-Left`
+fn parse_special(input: &str) -> Result<KeyPress, String> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut name = input;
+    while let Some((prefix, rest)) = name.split_once('-').filter(|(_, rest)| !rest.is_empty()) {
+        modifiers |= match prefix.to_ascii_uppercase().as_str() {
+            "C" => KeyModifiers::CONTROL,
+            "A" | "M" => KeyModifiers::ALT,
+            "S" => KeyModifiers::SHIFT,
+            _ => break,
+        };
+        name = rest;
+    }
+
+    let code = match name.to_ascii_lowercase().as_str() {
+        "esc" => KeyCode::Esc,
+        "enter" | "cr" | "return" => KeyCode::Enter,
+        "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+        "tab" => KeyCode::Tab,
+        "bs" | "backspace" => KeyCode::Backspace,
+        "del" | "delete" => KeyCode::Delete,
+        "space" => KeyCode::Char(' '),
+        "lt" => KeyCode::Char('<'),
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        lower => match (name.chars().next(), name.chars().count()) {
+            (Some(c), 1) => KeyCode::Char(c),
+            _ => match lower.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                Some(n @ 1..=12) => KeyCode::F(n),
+                _ => return Err(format!("无法识别的按键 <{input}>")),
+            },
+        },
+    };
+    Ok(KeyPress::new(code, modifiers))
+}
+
+/// 按键映射文件中的一项：一个按键或多个按键，空列表表示取消绑定
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum KeyBinding {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// 查找按键序列的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lookup {
+    Action(Action), // 完整匹配一个动作
+    Prefix,         // 是更长序列的开头，等待下一个键
+    None,           // 没有绑定
+}
+
+/// 一个模式的按键映射
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    bindings: Vec<(Vec<KeyPress>, Action)>,
+}
+
+impl Keymap {
+    /// 模式的默认按键映射
+    pub fn defaults(mode: AppMode) -> Self {
+        let keys: &[(&str, Action)] = match mode {
+            AppMode::Normal => &NORMAL_KEYS,
+            AppMode::Visual => &VISUAL_KEYS,
+            AppMode::Insert => &INSERT_KEYS,
+            AppMode::Command => &COMMAND_KEYS,
+            AppMode::Search => &SEARCH_KEYS,
+        };
+        let bindings = keys
+            .iter()
+            .map(|(keys, action)| (parse_keys(keys).expect("默认按键有效"), *action))
+            .collect();
+        Self { bindings }
+    }
+
+    /// 用户的设置覆盖默认按键：列出的动作只保留文件中给出的按键
+    pub fn apply(
+        &mut self,
+        mode: AppMode,
+        overrides: &BTreeMap<String, KeyBinding>,
+    ) -> Result<(), String> {
+        for (name, binding) in overrides {
+            let action = Action::from_name(name)
+                .filter(|action| self.bindings.iter().any(|(_, a)| a == action))
+                .ok_or_else(|| format!("{} 模式没有动作 `{name}`", mode.name()))?;
+            let keys = match binding {
+                KeyBinding::One(keys) => vec![keys.as_str()],
+                KeyBinding::Many(keys) => keys.iter().map(String::as_str).collect(),
+            };
+            let keys = keys
+                .into_iter()
+                .map(parse_keys)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("`{name}`: {e}"))?;
+            // 输入文本的模式中按键序列会和输入冲突
+            if mode.can_insert() && keys.iter().any(|keys| keys.len() > 1) {
+                return Err(format!("`{name}`: {} 模式只能绑定单个按键", mode.name()));
+            }
+
+            // 取消原来的绑定，新的按键放在原来的位置，保持提示的顺序
+            let at = self
+                .bindings
+                .iter()
+                .position(|(_, a)| *a == action)
+                .unwrap_or(self.bindings.len());
+            self.bindings.retain(|(_, a)| *a != action);
+            let at = at.min(self.bindings.len());
+            self.bindings
+                .splice(at..at, keys.into_iter().map(|keys| (keys, action)));
+        }
+        Ok(())
+    }
+
+    /// 检查冲突：同一个按键序列绑定了两个动作，或一个序列是另一个的开头
+    pub fn check_conflicts(&self) -> Result<(), String> {
+        for (i, (keys, action)) in self.bindings.iter().enumerate() {
+            for (other_keys, other) in &self.bindings[i + 1..] {
+                if keys == other_keys && action == other {
+                    continue;
+                }
+                if other_keys.starts_with(keys) || keys.starts_with(other_keys) {
+                    return Err(format!(
+                        "按键冲突：`{}`（{}）与 `{}`（{}）",
+                        format_keys(keys),
+                        action.name(),
+                        format_keys(other_keys),
+                        other.name()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 查找已输入的按键序列
+    pub fn lookup(&self, keys: &[KeyPress]) -> Lookup {
+        let mut prefix = false;
+        for (bound, action) in &self.bindings {
+            if bound == keys {
+                return Lookup::Action(*action);
+            }
+            prefix |= bound.starts_with(keys);
+        }
+        if prefix { Lookup::Prefix } else { Lookup::None }
+    }
+
+    /// 单个按键绑定的动作，用于输入文本的模式
+    pub fn action_for(&self, key: KeyPress) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(keys, _)| keys[..] == [key])
+            .map(|(_, action)| *action)
+    }
+
+    /// 动作的第一个按键，用于提示
+    pub fn key_for(&self, action: Action) -> Option<String> {
+        self.bindings
+            .iter()
+            .find(|(_, a)| *a == action)
+            .map(|(keys, _)| format_keys(keys))
+    }
+
+    /// 状态栏中的按键提示：需要提示的动作各取第一个按键
+    pub fn hints(&self) -> Vec<(String, &'static str)> {
+        let mut hints: Vec<(String, &'static str)> = Vec::new();
+        let mut seen = Vec::new();
+        for (keys, action) in &self.bindings {
+            if seen.contains(action) || !action.entry().3 {
+                continue;
+            }
+            seen.push(*action);
+            hints.push((format_keys(keys), action.description()));
+        }
+        hints
+    }
+
+    /// 帮助中列出的按键：每个动作一行，列出它的所有按键
+    pub fn help_entries(&self) -> Vec<(String, &'static str)> {
+        let mut entries: Vec<(Action, Vec<String>)> = Vec::new();
+        for (keys, action) in &self.bindings {
+            let keys = format_keys(keys);
+            match entries.iter_mut().find(|(a, _)| a == action) {
+                Some((_, all)) => all.push(keys),
+                None => entries.push((*action, vec![keys])),
+            }
+        }
+        entries
+            .into_iter()
+            .map(|(action, keys)| (keys.join(" "), action.description()))
+            .collect()
+    }
+}
+
+/// 动作前可以输入的前缀，用于帮助
+pub const PREFIX_KEYS: [(&str, &str); 2] = [
+    ("数字", "重复次数，例如 3j"),
+    (
+        "\"a–z \"-",
+        "指定寄存器，例如 \"ayy；\"- 为输入框中删除的文本",
+    ),
+];
+
+/// 每个模式的按键映射
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymaps {
+    pub normal: Keymap,
+    pub visual: Keymap,
+    pub insert: Keymap,
+    pub command: Keymap,
+    pub search: Keymap,
+}
+
+impl Default for Keymaps {
+    fn default() -> Self {
+        Self {
+            normal: Keymap::defaults(AppMode::Normal),
+            visual: Keymap::defaults(AppMode::Visual),
+            insert: Keymap::defaults(AppMode::Insert),
+            command: Keymap::defaults(AppMode::Command),
+            search: Keymap::defaults(AppMode::Search),
+        }
+    }
+}
+
+impl Keymaps {
+    /// 当前模式的按键映射
+    pub fn get(&self, mode: AppMode) -> &Keymap {
+        match mode {
+            AppMode::Normal => &self.normal,
+            AppMode::Visual => &self.visual,
+            AppMode::Insert => &self.insert,
+            AppMode::Command => &self.command,
+            AppMode::Search => &self.search,
+        }
+    }
+
+    pub fn get_mut(&mut self, mode: AppMode) -> &mut Keymap {
+        match mode {
+            AppMode::Normal => &mut self.normal,
+            AppMode::Visual => &mut self.visual,
+            AppMode::Insert => &mut self.insert,
+            AppMode::Command => &mut self.command,
+            AppMode::Search => &mut self.search,
+        }
+    }
+}
+
+/// 按键序列的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyOutcome {
+    Pending,                                     // 序列还没输入完，等待下一个键
+    Action(Action, Option<usize>, Option<char>), // 组成了完整的动作，附带计数和寄存器
+    Cancelled,                                   // 序列不构成任何动作，已作废
+    Unmatched,                                   // 单个按键没有绑定，交给其他地方处理
+}
+
+/// 计数的上限，输入更大的数字时按上限处理
+const MAX_COUNT: usize = 10_000;
+
+/// 尚未输入完整的按键序列，例如 `3`、`d`、`2g`、`"a`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PendingKeys {
+    register: Option<char>,  // `"x` 指定的寄存器
+    awaiting_register: bool, // 刚输入了 `"`，下一个键是寄存器名
+    count: Option<usize>,
+    keys: Vec<KeyPress>,
+    awaiting_argument: Option<Action>, // 等待寄存器名作为参数的动作
+}
+
+impl PendingKeys {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// 状态栏中显示的已输入部分，例如 `"a3d`
+    pub fn display(&self) -> String {
+        let register = match (self.register, self.awaiting_register) {
+            (Some(name), _) => format!("\"{name}"),
+            (None, true) => "\"".to_string(),
+            (None, false) => String::new(),
+        };
+        let count = self.count.map(|n| n.to_string()).unwrap_or_default();
+        format!("{register}{count}{}", format_keys(&self.keys))
+    }
+
+    /// 输入一个键；完成、作废或不匹配时序列都会被清空
+    ///
+    /// `recording` 为 true 时正在录制宏，录制键不再等待寄存器名，直接结束录制
+    pub fn feed(&mut self, key: KeyEvent, keymap: &Keymap, recording: bool) -> KeyOutcome {
+        let outcome = self.step(KeyPress::from(key), keymap, recording);
+        if outcome != KeyOutcome::Pending {
+            self.clear();
+        }
+        outcome
+    }
+
+    fn step(&mut self, key: KeyPress, keymap: &Keymap, recording: bool) -> KeyOutcome {
+        let plain_char = match key.code {
+            KeyCode::Char(c) if key.modifiers.is_empty() => Some(c),
+            _ => None,
+        };
+        // 动作的参数可以是任意字符，由执行动作的地方检查
+        if let Some(action) = self.awaiting_argument {
+            return match plain_char {
+                Some(name) => KeyOutcome::Action(action, self.count, Some(name)),
+                None => KeyOutcome::Cancelled,
+            };
+        }
+        // 和 Vim 一样，`"x` 为之后的动作指定寄存器
+        if self.awaiting_register {
+            self.awaiting_register = false;
+            return match plain_char.filter(|c| Registers::is_valid_name(*c)) {
+                Some(name) => {
+                    self.register = Some(name);
+                    KeyOutcome::Pending
+                }
+                None => KeyOutcome::Cancelled,
+            };
+        }
+        if self.keys.is_empty() && plain_char == Some('"') {
+            self.awaiting_register = true;
+            return KeyOutcome::Pending;
+        }
+
+        // 数字是计数，0 只在计数中间有效
+        if self.keys.is_empty()
+            && key.modifiers.is_empty()
+            && let KeyCode::Char(c @ '0'..='9') = key.code
+            && (c != '0' || self.count.is_some())
+        {
+            let digit = c as usize - '0' as usize;
+            self.count = Some(
+                self.count
+                    .unwrap_or(0)
+                    .saturating_mul(10)
+                    .saturating_add(digit)
+                    .min(MAX_COUNT),
+            );
+            return KeyOutcome::Pending;
+        }
+
+        self.keys.push(key);
+        match keymap.lookup(&self.keys) {
+            Lookup::Action(Action::RecordMacro) if recording => {
+                KeyOutcome::Action(Action::RecordMacro, self.count, None)
+            }
+            Lookup::Action(action) if action.takes_register() => {
+                self.awaiting_argument = Some(action);
+                KeyOutcome::Pending
+            }
+            Lookup::Action(action) => KeyOutcome::Action(action, self.count, self.register),
+            Lookup::Prefix => KeyOutcome::Pending,
+            Lookup::None
+                if self.keys.len() == 1 && self.count.is_none() && self.register.is_none() =>
+            {
+                KeyOutcome::Unmatched
+            }
+            Lookup::None => KeyOutcome::Cancelled,
+        }
+    }
+}
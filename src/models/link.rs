@@ -0,0 +1,76 @@
+use std::fmt;
+
+/// 任务上挂的链接或附件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskLink {
+    Url(String),    // 网址
+    Path(String),   // 本地文件或目录
+    Task(u64),      // 相关任务的 ID
+    BlockedBy(u64), // 依赖的任务，完成之前本任务被阻塞
+}
+
+/// 依赖链接的前缀，例如 `blocked-by:#12`
+const BLOCKED_BY_PREFIX: &str = "blocked-by:";
+
+impl TaskLink {
+    /// `#12` 为相关任务，`blocked-by:#12` 为依赖的任务，带协议的为网址，其余视为本地路径
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if input.is_empty() {
+            return None;
+        }
+
+        if let Some(rest) = input.strip_prefix(BLOCKED_BY_PREFIX) {
+            return rest
+                .trim()
+                .strip_prefix('#')?
+                .parse()
+                .ok()
+                .map(Self::BlockedBy);
+        }
+        if let Some(id) = input.strip_prefix('#') {
+            return id.parse().ok().map(Self::Task);
+        }
+        if input.contains("://") || input.starts_with("mailto:") {
+            return Some(Self::Url(input.to_string()));
+        }
+
+        // 展开开头的 ~
+        let path = match input.strip_prefix('~') {
+            Some(rest) => match std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")) {
+                Ok(home) => format!("{home}{rest}"),
+                Err(_) => input.to_string(),
+            },
+            None => input.to_string(),
+        };
+        Some(Self::Path(path))
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Self::Url(_) => "🔗",
+            Self::Path(_) => "📁",
+            Self::Task(_) => "↪",
+            Self::BlockedBy(_) => "⛔",
+        }
+    }
+
+    /// 链接指向的任务 ID
+    pub fn task_id(&self) -> Option<u64> {
+        match self {
+            Self::Task(id) | Self::BlockedBy(id) => Some(*id),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TaskLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Url(url) => write!(f, "{url}"),
+            Self::Path(path) => write!(f, "{path}"),
+            Self::Task(id) => write!(f, "#{id}"),
+            Self::BlockedBy(id) => write!(f, "{BLOCKED_BY_PREFIX}#{id}"),
+        }
+    }
+}
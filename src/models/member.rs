@@ -0,0 +1,44 @@
+use serde::Deserialize;
+
+/// 团队成员，来自配置中的 roster
+///
+/// ```toml
+/// user = "alice"
+///
+/// [[roster]]
+/// handle = "alice"
+/// name = "Alice Wang"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct Member {
+    pub handle: String,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+impl Member {
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.handle)
+    }
+
+    /// 名字各单词的首字母，例如 "Alice Wang" → "AW"；中文名取第一个字
+    pub fn initials(&self) -> String {
+        initials(self.display_name())
+    }
+}
+
+/// 从名字或 handle 中取缩写，最多两个字符
+pub fn initials(name: &str) -> String {
+    let words: Vec<&str> = name.split_whitespace().collect();
+    let initials: String = match words.as_slice() {
+        [] => String::new(),
+        [word] if word.is_ascii() => word.chars().take(2).collect(),
+        [word] => word.chars().take(1).collect(),
+        words => words
+            .iter()
+            .filter_map(|word| word.chars().next())
+            .take(2)
+            .collect(),
+    };
+    initials.to_uppercase()
+}
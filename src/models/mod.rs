@@ -1,11 +1,58 @@
 mod asciiart;
+mod bulk;
+mod command;
+mod comment;
+mod datetime;
+mod field;
+mod frontmatter;
+mod gtd;
+mod help;
+mod history;
+mod input;
+mod keymap;
+mod link;
+mod member;
+mod message;
+mod mode;
 mod music;
+mod pomodoro;
+mod progress;
+mod register;
+mod repeat;
+mod schedule;
+mod sort;
 mod task;
+mod template;
 mod theme;
+mod timetrack;
+mod urgency;
 mod window;
 
 pub use asciiart::*;
+pub use bulk::*;
+pub use command::*;
+pub use comment::*;
+pub use datetime::*;
+pub use field::*;
+pub use gtd::*;
+pub use help::*;
+pub use history::*;
+pub use input::*;
+pub use keymap::*;
+pub use link::*;
+pub use member::*;
+pub use message::*;
+pub use mode::*;
 pub use music::*;
+pub use pomodoro::*;
+pub use progress::*;
+pub use register::*;
+pub use repeat::*;
+pub use schedule::*;
+pub use sort::*;
 pub use task::*;
+pub use template::*;
 pub use theme::*;
+pub use timetrack::*;
+pub use urgency::*;
 pub use window::*;
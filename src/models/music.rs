@@ -18,6 +18,7 @@ pub enum PlaybackState {
 pub struct MusicPlayerState {
     pub current_playing_index: Option<usize>,
     pub playback_state: PlaybackState,
+    #[allow(dead_code)]
     pub current_position: Option<Duration>,
     pub volume: f32,
 }
@@ -0,0 +1,41 @@
+use time::{Duration, OffsetDateTime};
+
+/// 常用番茄钟时长（分钟），与设置窗口中的选项一一对应
+pub const POMODORO_PRESETS: [u32; 5] = [15, 20, 25, 30, 45];
+
+/// 默认番茄钟时长（分钟）
+pub const DEFAULT_POMODORO_MINUTES: u32 = 25;
+
+/// 可以设置的番茄钟时长范围（分钟）
+pub const POMODORO_MINUTES_RANGE: std::ops::RangeInclusive<u32> = 1..=600;
+
+/// 正在进行的番茄钟
+#[derive(Debug, Clone)]
+pub struct PomodoroSession {
+    pub task_index: Option<usize>, // 计入用时的任务
+    pub started_at: OffsetDateTime,
+    pub duration: Duration,
+}
+
+impl PomodoroSession {
+    pub fn new(task_index: Option<usize>, minutes: u32) -> Self {
+        Self {
+            task_index,
+            started_at: OffsetDateTime::now_utc(),
+            duration: Duration::minutes(minutes.into()),
+        }
+    }
+
+    /// 已经过的时间，不超过番茄钟时长
+    pub fn elapsed(&self) -> Duration {
+        (OffsetDateTime::now_utc() - self.started_at).clamp(Duration::ZERO, self.duration)
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.duration - self.elapsed()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.remaining() <= Duration::ZERO
+    }
+}
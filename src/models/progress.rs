@@ -0,0 +1,32 @@
+use crate::markdown;
+
+use super::{TaskStatus, TodoTask};
+
+impl TodoTask {
+    /// 完成进度（0–100）
+    ///
+    /// 手动设置的进度优先，否则按描述中已勾选的复选框比例计算；都没有时为 None
+    pub fn progress(&self) -> Option<u8> {
+        if let Some(progress) = self.manual_progress {
+            return Some(progress);
+        }
+        let (checked, total) = markdown::checklist_progress(&self.description);
+        (total > 0).then(|| (checked * 100 / total) as u8)
+    }
+
+    /// 进度已满但还没有标记完成
+    pub fn awaits_completion(&self) -> bool {
+        self.status != TaskStatus::Completed && self.progress() == Some(100)
+    }
+}
+
+/// 解析 `60` 或 `60%`，超出 0–100 时返回 None
+pub fn parse_progress(input: &str) -> Option<u8> {
+    input
+        .trim()
+        .trim_end_matches('%')
+        .trim()
+        .parse()
+        .ok()
+        .filter(|progress| *progress <= 100)
+}
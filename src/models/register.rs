@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+
+use super::TodoTask;
+
+/// 无名寄存器：没有用 `"x` 指定寄存器时使用，内容会同步到系统剪贴板
+pub const UNNAMED_REGISTER: char = '"';
+
+/// 小删除寄存器：输入框中按单词或整行删除的文本，不同步到系统剪贴板
+pub const SMALL_DELETE_REGISTER: char = '-';
+
+/// 寄存器中的内容
+#[derive(Debug, Clone)]
+pub enum RegisterContent {
+    Tasks(Vec<TodoTask>), // 复制或删除的任务，保留完整数据，粘贴时创建副本
+    Text(String),         // 输入框中删除或复制的文本
+}
+
+impl RegisterContent {
+    /// 纯文本形式的内容，任务每行一个标题
+    pub fn text(&self) -> String {
+        match self {
+            Self::Tasks(tasks) => tasks
+                .iter()
+                .map(|task| task.title.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Self::Text(text) => text.clone(),
+        }
+    }
+
+    /// 追加内容，任务和文本混合时都转成文本
+    fn append(&mut self, other: Self) {
+        match (&mut *self, other) {
+            (Self::Tasks(tasks), Self::Tasks(more)) => tasks.extend(more),
+            (Self::Text(text), Self::Text(more)) => text.push_str(&more),
+            (this, other) => *this = Self::Text(format!("{}\n{}", this.text(), other.text())),
+        }
+    }
+}
+
+/// 复制和粘贴用的寄存器：`"` 为无名寄存器，`-` 为小删除寄存器，a–z 为具名寄存器，用大写字母写入时追加
+#[derive(Debug, Clone, Default)]
+pub struct Registers {
+    contents: BTreeMap<char, RegisterContent>,
+    clipboard: Option<String>, // 等待通过 OSC 52 写入系统剪贴板的文本
+    small_delete_latest: bool, // 小删除寄存器比无名寄存器更新
+}
+
+impl Registers {
+    /// 可以用 `"x` 指定的寄存器名
+    pub fn is_valid_name(name: char) -> bool {
+        name == UNNAMED_REGISTER || name == SMALL_DELETE_REGISTER || name.is_ascii_alphabetic()
+    }
+
+    pub fn get(&self, name: char) -> Option<&RegisterContent> {
+        self.contents.get(&name.to_ascii_lowercase())
+    }
+
+    /// 写入寄存器，无名寄存器总是保存最近一次写入的内容并同步到剪贴板
+    pub fn store(&mut self, name: char, content: RegisterContent) {
+        let lower = name.to_ascii_lowercase();
+        let content = match self.contents.get_mut(&lower) {
+            Some(existing) if name.is_ascii_uppercase() => {
+                existing.append(content);
+                existing.clone()
+            }
+            _ => content,
+        };
+        if name != UNNAMED_REGISTER {
+            self.contents.insert(lower, content.clone());
+        }
+        self.clipboard = Some(content.text());
+        self.contents.insert(UNNAMED_REGISTER, content);
+        self.small_delete_latest = false;
+    }
+
+    /// 保存输入框中删除的文本，只写入小删除寄存器，不改变无名寄存器和系统剪贴板
+    pub fn store_small_delete(&mut self, text: String) {
+        self.contents
+            .insert(SMALL_DELETE_REGISTER, RegisterContent::Text(text));
+        self.small_delete_latest = true;
+    }
+
+    /// 输入框中 Ctrl-Y 粘贴的内容：小删除寄存器和无名寄存器中较新的一个
+    pub fn latest(&self) -> Option<&RegisterContent> {
+        if self.small_delete_latest {
+            self.get(SMALL_DELETE_REGISTER)
+        } else {
+            self.get(UNNAMED_REGISTER)
+        }
+    }
+
+    /// 保存录制的宏（Vim 写法的按键序列），不改变无名寄存器；大写字母表示追加
+    pub fn store_macro(&mut self, name: char, keys: String) {
+        let content = RegisterContent::Text(keys);
+        match self.contents.get_mut(&name.to_ascii_lowercase()) {
+            Some(existing) if name.is_ascii_uppercase() => existing.append(content),
+            _ => {
+                self.contents.insert(name.to_ascii_lowercase(), content);
+            }
+        }
+    }
+
+    /// 取出等待写入系统剪贴板的文本
+    pub fn take_clipboard(&mut self) -> Option<String> {
+        self.clipboard.take()
+    }
+}
@@ -0,0 +1,23 @@
+use super::{Action, DeferPreset, InputKind};
+
+/// 可以用 `.` 重复的修改
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Action(Action, Option<usize>, Option<char>), // 普通模式的动作及其计数和寄存器
+    Complete(usize), // 完成若干个任务（可视模式或 :done），重复时从当前行开始
+    Input(InputKind, String, usize), // 输入窗口或 :tag、:due 提交的值和作用的任务数
+    Defer(DeferPreset), // 推迟开始日期
+}
+
+/// `.` 能重复的修改，用于帮助
+pub const REPEAT_KEYS: [(&str, &str); 6] = [
+    ("删除 粘贴 复制任务", "沿用原来的计数和寄存器"),
+    ("优先级 上移/下移", "沿用原来的计数"),
+    ("可视模式完成 :done", "从当前行开始完成同样多的任务"),
+    (
+        "可视模式标签/截止日期 :tag :due",
+        "从当前行开始修改同样多的任务",
+    ),
+    ("进度 预估用时", "当前任务设为同样的值"),
+    ("推迟", "当前任务按同样的方式推迟"),
+];
@@ -0,0 +1,79 @@
+use time::{Duration, OffsetDateTime, Time, Weekday};
+
+use super::{ActivityKind, TaskStatus, TodoTask};
+
+/// 推迟（snooze）任务开始日期的预设
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeferPreset {
+    OneHour,
+    Tomorrow,
+    NextWeek,
+}
+
+impl DeferPreset {
+    pub const ALL: [DeferPreset; 3] = [Self::OneHour, Self::Tomorrow, Self::NextWeek];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::OneHour => "1 小时后",
+            Self::Tomorrow => "明天 09:00",
+            Self::NextWeek => "下周一 09:00",
+        }
+    }
+
+    /// 计算推迟后的开始时间；`current` 为任务原来的开始时间，超出可表示的日期范围时返回 None
+    pub fn apply(
+        &self,
+        current: Option<OffsetDateTime>,
+        now: OffsetDateTime,
+    ) -> Option<OffsetDateTime> {
+        let morning = Time::from_hms(9, 0, 0).unwrap_or(Time::MIDNIGHT);
+        match self {
+            // 已经排在未来的任务在原计划上再推迟一小时
+            Self::OneHour => current
+                .filter(|start| *start > now)
+                .unwrap_or(now)
+                .checked_add(Duration::hours(1)),
+            Self::Tomorrow => Some(
+                (now.date() + Duration::days(1))
+                    .with_time(morning)
+                    .assume_utc(),
+            ),
+            Self::NextWeek => Some(
+                now.date()
+                    .next_occurrence(Weekday::Monday)
+                    .with_time(morning)
+                    .assume_utc(),
+            ),
+        }
+    }
+}
+
+impl TodoTask {
+    /// 没有开始日期或开始日期已到的任务才是可执行的
+    pub fn is_actionable(&self, now: OffsetDateTime) -> bool {
+        self.scheduled_date.is_none_or(|start| start <= now)
+    }
+
+    /// 是否计划在今天开始（已完成的任务不算）
+    pub fn is_scheduled_today(&self, now: OffsetDateTime) -> bool {
+        self.status != TaskStatus::Completed
+            && self
+                .scheduled_date
+                .is_some_and(|start| start.date() == now.date())
+    }
+
+    /// 按预设推迟开始日期，无法推迟时任务保持不变
+    pub fn defer(&mut self, preset: DeferPreset) -> Result<(), String> {
+        let now = OffsetDateTime::now_utc();
+        let to = preset
+            .apply(self.scheduled_date, now)
+            .ok_or("开始日期已到可表示的最大日期，无法再推迟")?;
+        self.record(ActivityKind::Rescheduled {
+            from: self.scheduled_date,
+            to: Some(to),
+        });
+        self.scheduled_date = Some(to);
+        Ok(())
+    }
+}
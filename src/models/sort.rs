@@ -0,0 +1,127 @@
+use std::cmp::Ordering;
+use std::ops::Range;
+
+use super::{FieldDef, TaskStatus, TodoTask};
+
+/// 任务列表的排序方式
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum SortMode {
+    #[default]
+    Urgency, // 紧急度从高到低，已完成的排在最后
+    Manual,        // 手动调整的顺序
+    Added,         // 添加顺序
+    Field(String), // 按自定义字段排序，没有值的任务排在最后
+}
+
+/// 排序时需要的上下文，`urgency` 与 `tasks` 按下标一一对应
+pub struct SortContext<'a> {
+    pub tasks: &'a [TodoTask],
+    pub fields: &'a [FieldDef],
+    pub urgency: &'a [f64],
+}
+
+impl SortMode {
+    pub fn label(&self, fields: &[FieldDef]) -> String {
+        match self {
+            Self::Urgency => "紧急度".to_string(),
+            Self::Manual => "手动顺序".to_string(),
+            Self::Added => "添加顺序".to_string(),
+            Self::Field(name) => fields
+                .iter()
+                .find(|def| &def.name == name)
+                .map_or_else(|| name.clone(), |def| def.label().to_string()),
+        }
+    }
+
+    /// 按名称查找排序方式，可以用英文名、中文名称或自定义字段名
+    pub fn parse(name: &str, fields: &[FieldDef]) -> Option<Self> {
+        match name {
+            "urgency" | "紧急度" => Some(Self::Urgency),
+            "manual" | "手动顺序" => Some(Self::Manual),
+            "added" | "添加顺序" => Some(Self::Added),
+            _ => fields
+                .iter()
+                .find(|def| def.name == name || def.label() == name)
+                .map(|def| Self::Field(def.name.clone())),
+        }
+    }
+
+    /// 依次切换：紧急度 → 手动顺序 → 添加顺序 → 各个自定义字段 → 紧急度
+    pub fn next(&self, fields: &[FieldDef]) -> Self {
+        let next = match self {
+            Self::Urgency => return Self::Manual,
+            Self::Manual => return Self::Added,
+            Self::Added => 0,
+            Self::Field(name) => fields
+                .iter()
+                .position(|def| &def.name == name)
+                .map_or(fields.len(), |i| i + 1),
+        };
+        match fields.get(next) {
+            Some(def) => Self::Field(def.name.clone()),
+            None => Self::Urgency,
+        }
+    }
+
+    /// 比较下标为 `a` 和 `b` 的两个任务
+    pub fn compare(&self, a: usize, b: usize, context: &SortContext) -> Ordering {
+        let (task_a, task_b) = (&context.tasks[a], &context.tasks[b]);
+        match self {
+            Self::Urgency => {
+                let completed = |task: &TodoTask| task.status == TaskStatus::Completed;
+                completed(task_a)
+                    .cmp(&completed(task_b))
+                    .then_with(|| context.urgency[b].total_cmp(&context.urgency[a]))
+            }
+            Self::Manual => task_a.position.cmp(&task_b.position),
+            Self::Added => a.cmp(&b),
+            Self::Field(name) => {
+                let Some(def) = context.fields.iter().find(|def| &def.name == name) else {
+                    return Ordering::Equal;
+                };
+                match (task_a.fields.get(name), task_b.fields.get(name)) {
+                    (Some(x), Some(y)) => def.compare(x, y),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                }
+            }
+        }
+    }
+}
+
+/// 手动调整顺序时的移动方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Movement {
+    Up,     // 上移一行
+    Down,   // 下移一行
+    Top,    // 移到最前
+    Bottom, // 移到最后
+}
+
+impl Movement {
+    /// 把 `order` 中 `range` 范围内的元素整体移动，返回移动后该范围的起点
+    pub fn apply<T>(&self, order: &mut [T], range: Range<usize>) -> usize {
+        let Range { start, end } = range;
+        let len = end - start;
+        match self {
+            Self::Up if start > 0 => {
+                order[start - 1..end].rotate_left(1);
+                start - 1
+            }
+            Self::Down if end < order.len() => {
+                order[start..=end].rotate_right(1);
+                start + 1
+            }
+            Self::Top => {
+                order[..end].rotate_left(start);
+                0
+            }
+            Self::Bottom => {
+                order[start..].rotate_left(len);
+                order.len() - len
+            }
+            _ => start,
+        }
+    }
+}
@@ -1,6 +1,9 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::ops::Range;
 use time::{Duration, OffsetDateTime};
 
+use super::{Activity, ActivityKind, Comment, Estimate, FieldValue, TaskLink};
+
 // 任务状态（系统内置，不可自定义）
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TaskStatus {
@@ -19,6 +22,61 @@ impl TaskStatus {
             Self::DueToday => "🟡",
         }
     }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Completed => "已完成",
+            Self::Todo => "待办",
+            Self::Overdue => "已逾期",
+            Self::DueToday => "今日到期",
+        }
+    }
+}
+
+// 任务优先级
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    High,   // 高
+    Medium, // 中
+    Low,    // 低
+}
+
+impl Priority {
+    /// 接受 H/M/L、high/medium/low 或 高/中/低
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_lowercase().as_str() {
+            "h" | "high" | "高" => Some(Self::High),
+            "m" | "medium" | "中" => Some(Self::Medium),
+            "l" | "low" | "低" => Some(Self::Low),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::High => "H",
+            Self::Medium => "M",
+            Self::Low => "L",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::High => "高",
+            Self::Medium => "中",
+            Self::Low => "低",
+        }
+    }
+
+    /// 依次切换：无 → 高 → 中 → 低 → 无
+    pub fn cycle(priority: Option<Self>) -> Option<Self> {
+        match priority {
+            None => Some(Self::High),
+            Some(Self::High) => Some(Self::Medium),
+            Some(Self::Medium) => Some(Self::Low),
+            Some(Self::Low) => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -38,28 +96,90 @@ impl Tag {
 
 #[derive(Debug, Clone)]
 pub struct TodoTask {
+    pub id: u64, // 任务 ID，由 App 分配，用于任务之间的链接
     pub title: String,
     pub description: String,
     pub status: TaskStatus,
+    pub priority: Option<Priority>, // 优先级，参与紧急度计算
+    pub position: u64,              // 手动排序时的位置，由 App 分配
+    pub assignee: Option<String>,   // 负责人的 handle
+    pub created_by: Option<String>, // 创建者的 handle
+    pub list: Option<String>,       // 所属清单，None 表示还在收件箱
+    pub contexts: BTreeSet<String>, // 情境，例如 home、office，不带 @
     pub tags: HashSet<Tag>,
-    pub created_at: OffsetDateTime,          // 创建时间
-    pub due_date: Option<OffsetDateTime>,    // 截止日期
-    pub finish_date: Option<OffsetDateTime>, // 完成日期
+    pub created_at: OffsetDateTime,             // 创建时间
+    pub scheduled_date: Option<OffsetDateTime>, // 计划开始日期，之前不在默认视图中显示
+    pub due_date: Option<OffsetDateTime>,       // 截止日期
+    pub finish_date: Option<OffsetDateTime>,    // 完成日期
+    pub estimate: Option<Estimate>,             // 预估用时
+    pub time_spent: Duration,                   // 已累计的实际用时
+    pub tracking_since: Option<OffsetDateTime>, // 正在计时的开始时间
+    pub pomodoros_done: u32,                    // 已完成的番茄钟数
+    pub manual_progress: Option<u8>,            // 手动设置的进度，未设置时按清单计算
+    pub links: Vec<TaskLink>,                   // 链接、附件和关联任务
+    pub history: Vec<Activity>,                 // 变更历史，按时间先后排列
+    pub comments: Vec<Comment>,                 // 备注，按添加顺序排列
+    pub fields: BTreeMap<String, FieldValue>,   // 自定义字段，键为配置中的字段名
+}
+
+impl Default for TodoTask {
+    fn default() -> Self {
+        Self::new(String::new(), String::new())
+    }
 }
 
 impl TodoTask {
     pub fn new(title: String, description: String) -> Self {
+        let created_at = OffsetDateTime::now_utc();
         Self {
+            id: 0,
             title,
             description,
             status: TaskStatus::Todo,
+            priority: None,
+            position: 0,
+            assignee: None,
+            created_by: None,
+            list: None,
+            contexts: BTreeSet::new(),
             tags: HashSet::new(),
-            created_at: OffsetDateTime::now_utc(), // 修改：Utc::now() -> OffsetDateTime::now_utc()
+            created_at, // 修改：Utc::now() -> OffsetDateTime::now_utc()
+            scheduled_date: None,
             due_date: None,
             finish_date: None,
+            estimate: None,
+            time_spent: Duration::ZERO,
+            tracking_since: None,
+            pomodoros_done: 0,
+            manual_progress: None,
+            links: Vec::new(),
+            history: vec![Activity {
+                at: created_at,
+                kind: ActivityKind::Created,
+            }],
+            comments: Vec::new(),
+            fields: BTreeMap::new(),
         }
     }
 
+    /// 复制任务：保留标题、描述（含清单）、标签、日期和自定义字段，
+    /// 完成状态、用时、备注和历史从头开始
+    pub fn duplicate(&self) -> Self {
+        let mut copy = Self::new(self.title.clone(), self.description.clone());
+        copy.priority = self.priority;
+        copy.assignee = self.assignee.clone();
+        copy.list = self.list.clone();
+        copy.contexts = self.contexts.clone();
+        copy.tags = self.tags.clone();
+        copy.scheduled_date = self.scheduled_date;
+        copy.due_date = self.due_date;
+        copy.estimate = self.estimate;
+        copy.links = self.links.clone();
+        copy.fields = self.fields.clone();
+        copy.update_status();
+        copy
+    }
+
     pub fn add_tag(&mut self, tag_name: String) {
         self.tags.insert(Tag::new(tag_name));
     }
@@ -68,10 +188,33 @@ impl TodoTask {
         self.tags.retain(|tag| tag.name() != tag_name);
     }
 
+    /// 标题、描述、标签和备注中是否包含关键字（忽略大小写）
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        let contains = |text: &str| text.to_lowercase().contains(&query);
+
+        contains(&self.title)
+            || contains(&self.description)
+            || self.tags.iter().any(|tag| contains(tag.name()))
+            || self.comments.iter().any(|comment| contains(&comment.text))
+    }
+
     pub fn complete(&mut self) {
+        self.stop_tracking();
         self.status = TaskStatus::Completed;
+        self.finish_date = Some(OffsetDateTime::now_utc());
+        self.record(ActivityKind::Completed);
+    }
+
+    /// 重新打开已完成的任务，状态按截止日期重新计算
+    pub fn reopen(&mut self) {
+        self.status = TaskStatus::Todo;
+        self.finish_date = None;
+        self.update_status();
+        self.record(ActivityKind::Reopened);
     }
 
+    #[allow(dead_code)]
     pub fn set_due_date(&mut self, due_date: OffsetDateTime) {
         self.due_date = Some(due_date);
         self.update_status();
@@ -97,3 +240,38 @@ impl TodoTask {
         }
     }
 }
+
+/// 文本中关键字出现的位置（忽略大小写，互不重叠），用于高亮搜索结果
+pub fn match_ranges(text: &str, query: &str) -> Vec<Range<usize>> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    let mut ranges = Vec::new();
+    if query.is_empty() {
+        return ranges;
+    }
+    let mut start = 0;
+    while start < text.len() {
+        match match_at(&text[start..], &query) {
+            Some(len) => {
+                ranges.push(start..start + len);
+                start += len;
+            }
+            None => start += text[start..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    ranges
+}
+
+/// `text` 的开头是否匹配小写的 `query`，返回匹配部分的字节长度
+fn match_at(text: &str, query: &[char]) -> Option<usize> {
+    let mut lowered = Vec::with_capacity(query.len());
+    for (offset, c) in text.char_indices() {
+        lowered.extend(c.to_lowercase());
+        if !query.starts_with(&lowered) {
+            return None;
+        }
+        if lowered.len() == query.len() {
+            return Some(offset + c.len_utf8());
+        }
+    }
+    None
+}
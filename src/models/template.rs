@@ -0,0 +1,115 @@
+use std::fmt::Write;
+
+use serde::Deserialize;
+use time::{Duration, OffsetDateTime};
+
+use super::{TodoTask, format_datetime};
+
+/// 配置中定义的任务模板
+///
+/// ```toml
+/// [[templates]]
+/// name = "代码评审"
+/// title = "Review {date}"
+/// tags = ["review"]
+/// checklist = ["阅读 diff", "本地运行测试"]
+/// due = "2d"
+/// ```
+///
+/// `title` 和 `description` 中可以使用 `{date}`、`{time}`、`{weekday}` 占位符
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TaskTemplate {
+    pub name: String,
+    pub title: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    pub checklist: Vec<String>, // 追加到描述末尾的复选框
+    pub due: Option<String>,    // 相对创建时间的截止偏移，例如 3d、1w、2h30m
+}
+
+impl TaskTemplate {
+    /// 截止日期偏移，格式错误时返回错误信息
+    pub fn due_offset(&self) -> Result<Option<Duration>, String> {
+        match &self.due {
+            None => Ok(None),
+            Some(due) => parse_offset(due).map(Some).ok_or_else(|| {
+                format!(
+                    "模板 `{}` 的 due `{due}` 格式错误，例如 3d、1w、2h30m",
+                    self.name
+                )
+            }),
+        }
+    }
+
+    /// 替换占位符后的标题
+    pub fn title_for(&self, now: OffsetDateTime) -> String {
+        expand(&self.title, now)
+    }
+
+    /// 替换占位符后的描述，清单项追加为未勾选的复选框
+    pub fn description_for(&self, now: OffsetDateTime) -> String {
+        let mut description = expand(&self.description, now);
+        if !self.checklist.is_empty() {
+            if !description.is_empty() {
+                description.push_str("\n\n");
+            }
+            for item in &self.checklist {
+                let _ = writeln!(description, "- [ ] {}", expand(item, now));
+            }
+        }
+        description.trim_end().to_string()
+    }
+
+    /// 把模板的标签和截止日期应用到新建的任务上
+    pub fn apply(&self, task: &mut TodoTask) {
+        for tag in &self.tags {
+            task.add_tag(tag.clone());
+        }
+        if let Ok(Some(offset)) = self.due_offset() {
+            task.due_date = task.created_at.checked_add(offset);
+        }
+        task.update_status();
+    }
+}
+
+fn expand(text: &str, now: OffsetDateTime) -> String {
+    let datetime = format_datetime(now);
+    let (date, time) = datetime.split_once(' ').unwrap_or((&datetime, ""));
+    let weekday = ["一", "二", "三", "四", "五", "六", "日"]
+        [now.weekday().number_days_from_monday() as usize];
+
+    text.replace("{date}", date)
+        .replace("{time}", time)
+        .replace("{weekday}", &format!("周{weekday}"))
+}
+
+/// 解析 `3d`、`1w`、`2h30m` 这类相对时间，数字过大溢出时返回 None
+pub fn parse_offset(input: &str) -> Option<Duration> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return None;
+    }
+
+    let mut total = Duration::ZERO;
+    let mut number = String::new();
+    for c in input.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let n: i64 = number.parse().ok()?;
+        number.clear();
+        let unit = match c {
+            'w' => Duration::WEEK,
+            'd' => Duration::DAY,
+            'h' => Duration::HOUR,
+            'm' => Duration::MINUTE,
+            _ => return None,
+        };
+        let seconds = unit.whole_seconds().checked_mul(n)?;
+        total = total.checked_add(Duration::seconds(seconds))?;
+    }
+    // 末尾不能有没有单位的数字
+    number.is_empty().then_some(total)
+}
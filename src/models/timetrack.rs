@@ -0,0 +1,109 @@
+use time::{Duration, OffsetDateTime};
+
+use super::TodoTask;
+
+/// 任务的预估用时
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Estimate {
+    Minutes(u32),
+    Pomodoros(u32),
+}
+
+impl Estimate {
+    /// 解析 `90`、`90m`、`1h30m`、`3p`、`3🍅` 这类输入，总分钟数超出 u32 时返回 None
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim().to_lowercase();
+        if input.is_empty() {
+            return None;
+        }
+
+        if let Some(count) = input.strip_suffix("🍅").or_else(|| input.strip_suffix('p')) {
+            return count.trim().parse().ok().map(Self::Pomodoros);
+        }
+
+        if let Ok(minutes) = input.parse() {
+            return Some(Self::Minutes(minutes));
+        }
+
+        let (hours, rest) = match input.split_once('h') {
+            Some((hours, rest)) => (hours.trim().parse::<u32>().ok()?, rest.trim()),
+            None => (0, input.as_str()),
+        };
+        let minutes = match rest.strip_suffix('m') {
+            Some(minutes) => minutes.trim().parse::<u32>().ok()?,
+            None if rest.is_empty() => 0,
+            None => return None,
+        };
+        hours
+            .checked_mul(60)
+            .and_then(|hours| hours.checked_add(minutes))
+            .map(Self::Minutes)
+    }
+
+    /// 换算成分钟，番茄数按当前番茄钟时长计算，超出 u32 时取最大值
+    pub fn minutes(&self, pomodoro_minutes: u32) -> u32 {
+        match self {
+            Self::Minutes(minutes) => *minutes,
+            Self::Pomodoros(count) => count.saturating_mul(pomodoro_minutes),
+        }
+    }
+
+    /// 用于编辑和显示的文本形式
+    pub fn label(&self) -> String {
+        match self {
+            Self::Minutes(minutes) => format_duration(Duration::minutes((*minutes).into())),
+            Self::Pomodoros(count) => format!("{count}p"),
+        }
+    }
+}
+
+/// 格式化时长，例如 1h05m、25m
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.whole_minutes().max(0);
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{minutes}m"),
+        (hours, 0) => format!("{hours}h"),
+        (hours, minutes) => format!("{hours}h{minutes:02}m"),
+    }
+}
+
+impl TodoTask {
+    pub fn is_tracking(&self) -> bool {
+        self.tracking_since.is_some()
+    }
+
+    pub fn start_tracking(&mut self) {
+        if self.tracking_since.is_none() {
+            self.tracking_since = Some(OffsetDateTime::now_utc());
+        }
+    }
+
+    /// 停止计时，把本段时间累加到实际用时
+    pub fn stop_tracking(&mut self) {
+        if let Some(since) = self.tracking_since.take() {
+            self.add_time_spent(OffsetDateTime::now_utc() - since);
+        }
+    }
+
+    pub fn add_time_spent(&mut self, duration: Duration) {
+        if duration.is_positive() {
+            self.time_spent += duration;
+        }
+    }
+
+    /// 实际用时，包括正在计时的部分
+    pub fn actual_time(&self) -> Duration {
+        let running = self
+            .tracking_since
+            .map(|since| OffsetDateTime::now_utc() - since)
+            .unwrap_or(Duration::ZERO);
+        self.time_spent + running
+    }
+
+    /// 实际用时是否已超过预估
+    pub fn is_over_estimate(&self, pomodoro_minutes: u32) -> bool {
+        self.estimate.is_some_and(|estimate| {
+            self.actual_time() > Duration::minutes(estimate.minutes(pomodoro_minutes).into())
+        })
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use super::{Priority, TaskLink, TaskStatus, TodoTask};
+
+/// 紧急度各项的权重，参考 Taskwarrior 的默认值
+///
+/// ```toml
+/// [urgency]
+/// due = 12.0
+/// blocked = -5.0
+///
+/// [urgency.tags]
+/// 紧急 = 5.0
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct UrgencyWeights {
+    pub due: f64,                   // 截止日期临近程度
+    pub priority_high: f64,         // 高优先级
+    pub priority_medium: f64,       // 中优先级
+    pub priority_low: f64,          // 低优先级
+    pub age: f64,                   // 创建时间越久越紧急
+    pub age_max_days: f64,          // 达到满分所需的天数
+    pub blocked: f64,               // 被未完成的依赖任务阻塞
+    pub tags: HashMap<String, f64>, // 特定标签的加减分
+}
+
+impl Default for UrgencyWeights {
+    fn default() -> Self {
+        Self {
+            due: 12.0,
+            priority_high: 6.0,
+            priority_medium: 3.9,
+            priority_low: 1.8,
+            age: 2.0,
+            age_max_days: 365.0,
+            blocked: -5.0,
+            tags: HashMap::new(),
+        }
+    }
+}
+
+/// 紧急度及其组成，用于在详情中解释排名
+#[derive(Debug, Clone, Default)]
+pub struct Urgency {
+    pub score: f64,
+    pub factors: Vec<(String, f64)>, // (说明, 得分)
+}
+
+impl Urgency {
+    fn add(&mut self, label: impl Into<String>, value: f64) {
+        if value != 0.0 {
+            self.score += value;
+            self.factors.push((label.into(), value));
+        }
+    }
+}
+
+impl TodoTask {
+    /// 依赖的任务（`blocked-by:#ID` 链接）中有未完成的，视为被阻塞；`#ID` 只表示相关，不算阻塞
+    pub fn is_blocked(&self, tasks: &[TodoTask]) -> bool {
+        self.links.iter().any(|link| match link {
+            TaskLink::BlockedBy(id) => tasks
+                .iter()
+                .any(|task| task.id == *id && task.status != TaskStatus::Completed),
+            _ => false,
+        })
+    }
+
+    /// 计算紧急度，已完成的任务为 0
+    pub fn urgency(
+        &self,
+        weights: &UrgencyWeights,
+        tasks: &[TodoTask],
+        now: OffsetDateTime,
+    ) -> Urgency {
+        let mut urgency = Urgency::default();
+        if self.status == TaskStatus::Completed {
+            return urgency;
+        }
+
+        // 逾期 7 天及以上为 1.0，14 天后到期为 0.2，中间线性变化
+        if let Some(due) = self.due_date {
+            let days_overdue = (now - due).as_seconds_f64() / 86_400.0;
+            let proximity = if days_overdue >= 7.0 {
+                1.0
+            } else if days_overdue >= -14.0 {
+                (days_overdue + 14.0) * 0.8 / 21.0 + 0.2
+            } else {
+                0.2
+            };
+            urgency.add("截止", proximity * weights.due);
+        }
+
+        if let Some(priority) = self.priority {
+            let weight = match priority {
+                Priority::High => weights.priority_high,
+                Priority::Medium => weights.priority_medium,
+                Priority::Low => weights.priority_low,
+            };
+            urgency.add(format!("优先级{}", priority.label()), weight);
+        }
+
+        if weights.age_max_days > 0.0 {
+            let age_days = (now - self.created_at).as_seconds_f64().max(0.0) / 86_400.0;
+            urgency.add(
+                "存在时间",
+                (age_days / weights.age_max_days).min(1.0) * weights.age,
+            );
+        }
+
+        if self.is_blocked(tasks) {
+            urgency.add("被阻塞", weights.blocked);
+        }
+
+        let mut tags: Vec<&str> = self.tags.iter().map(|tag| tag.name()).collect();
+        tags.sort_unstable();
+        for tag in tags {
+            if let Some(&weight) = weights.tags.get(tag) {
+                urgency.add(format!("#{tag}"), weight);
+            }
+        }
+
+        urgency
+    }
+}
@@ -1,11 +1,100 @@
+use super::TextInput;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum WindowType {
     CreateTask,
     PomodoroSettings,
     Settings,
-    Search,
+    Input,
+    Defer,
+    Links,
+    Templates,
+    BulkDates,
+    Confirm,
+    Assignee,
+    Inbox,
+    MoveToList,
+}
+
+/// 通用输入窗口的用途
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputKind {
+    Estimate, // 设置预估用时
+    Link,     // 添加链接或附件
+    Comment,  // 追加备注
+    Filter,   // 按自定义字段筛选
+    Progress, // 设置进度
+    Capture,  // 快速记录到收件箱
+    Tags,     // 批量修改标签
+    Due,      // 批量设置截止日期
+}
+
+impl InputKind {
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Estimate => "⏳ 预估用时 ",
+            Self::Link => "🔗 添加链接 ",
+            Self::Comment => "💬 添加备注 ",
+            Self::Filter => "🔎 筛选 ",
+            Self::Progress => "📊 进度 ",
+            Self::Capture => "📥 快速记录 ",
+            Self::Tags => "🏷 修改标签 ",
+            Self::Due => "📅 截止日期 ",
+        }
+    }
+
+    pub fn placeholder(&self) -> &'static str {
+        match self {
+            Self::Estimate => "例如 90m、1h30m、4p（番茄数），留空清除",
+            Self::Link => "网址、本地路径，#ID 关联其他任务，blocked-by:#ID 依赖其他任务",
+            Self::Comment => "例如：已联系供应商，等待回复",
+            Self::Filter => "例如 points>=3、customer=ACME、!ticket，留空清除",
+            Self::Progress => "0–100，留空则按清单计算",
+            Self::Capture => "写下想法即可，可附带 @home 这样的情境",
+            Self::Tags => "+标签 添加，-标签 移除，例如 +紧急 -以后",
+            Self::Due => "例如 2025-06-01 18:00 或 3d，留空清除",
+        }
+    }
 }
 
+/// 处理收件箱时当前的步骤
+#[derive(Debug, Clone, PartialEq)]
+pub enum InboxStep {
+    Menu,                        // 选择要做的操作
+    List { selected: usize },    // 选择清单
+    Context { selected: usize }, // 勾选情境
+    Due { value: TextInput },    // 输入截止日期
+}
+
+impl InboxStep {
+    /// 当前步骤的按键提示
+    pub fn key_hints(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Menu => &[
+                ("l", "清单"),
+                ("c", "情境"),
+                ("d", "截止"),
+                ("x", "删除"),
+                ("n", "跳过"),
+                ("Esc", "结束"),
+            ],
+            Self::List { .. } => &[("j/k", "选择"), ("Enter", "归入"), ("Esc", "结束")],
+            Self::Context { .. } => &[("Space", "勾选"), ("Enter", "返回"), ("Esc", "结束")],
+            Self::Due { .. } => &[("Enter", "确定"), ("Esc", "结束")],
+        }
+    }
+}
+
+/// 音乐播放列表的按键
+pub const MUSIC_LIST_KEYS: [(&str, &str); 3] = [
+    ("↑/k ↓/j", "选择"),
+    ("Enter", "播放"),
+    ("Space", "暂停/继续"),
+];
+
+/// 列表选择窗口的按键
+const LIST_WINDOW_KEYS: [(&str, &str); 3] = [("j/k", "选择"), ("Enter", "确定"), ("Esc", "取消")];
+
 #[derive(Debug, Clone)]
 pub struct WindowLayout {
     pub x: u16,
@@ -22,26 +111,141 @@ pub struct ActiveWindow {
     pub is_visible: bool,
 }
 
+impl ActiveWindow {
+    /// 窗口当前是否在输入文本，此时应处于插入模式
+    pub fn accepts_text(&self) -> bool {
+        matches!(
+            self.data,
+            WindowData::CreateTask { .. }
+                | WindowData::Input { .. }
+                | WindowData::Inbox {
+                    step: InboxStep::Due { .. },
+                    ..
+                }
+        )
+    }
+
+    /// 窗口的名称，用于帮助
+    pub fn title(&self) -> &'static str {
+        match &self.data {
+            WindowData::Input { kind, .. } => kind.title().trim_end(),
+            _ => match self.window_type {
+                WindowType::CreateTask => "新建任务",
+                WindowType::PomodoroSettings => "番茄钟设置",
+                WindowType::Settings => "设置",
+                WindowType::Input => "输入",
+                WindowType::Defer => "推迟",
+                WindowType::Links => "链接",
+                WindowType::Templates => "模板",
+                WindowType::BulkDates => "批量调整日期",
+                WindowType::Confirm => "确认",
+                WindowType::Assignee => "负责人",
+                WindowType::Inbox => "处理收件箱",
+                WindowType::MoveToList => "移到清单",
+            },
+        }
+    }
+
+    /// 窗口中可以使用的按键，用于帮助；输入框的编辑按键见 `TEXT_INPUT_KEYS`
+    pub fn key_hints(&self) -> Vec<(&'static str, &'static str)> {
+        match &self.data {
+            WindowData::CreateTask { .. } => vec![
+                ("Tab/Shift-Tab", "切换标题/描述/自定义字段"),
+                ("Enter", "创建"),
+                ("Alt-Enter Ctrl-J", "描述中换行"),
+                ("Ctrl-O", "在编辑器中编辑描述"),
+                ("Ctrl-T", "从模板新建"),
+                ("Esc", "取消"),
+            ],
+            WindowData::PomodoroSettings { .. } => {
+                let mut hints = vec![
+                    ("Tab", "切换常用时长/自定义/音乐"),
+                    ("j/k", "选择常用时长"),
+                    ("Enter", "开始番茄钟"),
+                ];
+                hints.extend(MUSIC_LIST_KEYS);
+                hints.push(("Esc", "关闭"));
+                hints
+            }
+            WindowData::Settings { .. } => {
+                let mut hints = vec![("Tab j/k", "切换选项"), ("Space", "开启/关闭选项")];
+                hints.extend(MUSIC_LIST_KEYS);
+                hints.push(("Esc", "关闭"));
+                hints
+            }
+            WindowData::Links { .. } => vec![
+                ("j/k", "选择"),
+                ("Enter", "打开"),
+                ("d/Delete", "删除"),
+                ("Esc", "关闭"),
+            ],
+            WindowData::Confirm { .. } => vec![("y/Enter", "确定"), ("n/Esc", "取消")],
+            WindowData::Inbox { step, .. } => step.key_hints().to_vec(),
+            WindowData::Input { .. } => vec![("Enter", "确定"), ("Esc", "取消")],
+            WindowData::Defer { .. }
+            | WindowData::Templates { .. }
+            | WindowData::BulkDates { .. }
+            | WindowData::Assignee { .. }
+            | WindowData::MoveToList { .. } => LIST_WINDOW_KEYS.to_vec(),
+            WindowData::Empty => vec![("Esc", "关闭")],
+        }
+    }
+}
+
+/// 需要用户确认的操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfirmAction {
+    CompleteTask(u64),     // 进度达到 100% 后标记任务完成
+    DeleteTasks(Vec<u64>), // 删除任务
+}
+
 #[derive(Debug, Clone)]
 pub enum WindowData {
     CreateTask {
-        title: String,
-        description: String,
-        current_field: usize,
-        cursor_position: usize,
+        title: TextInput,
+        description: TextInput,  // 多行
+        fields: Vec<TextInput>,  // 自定义字段，与配置中的 fields 一一对应
+        current_field: usize,    // 0 为标题，1 为描述，之后依次为自定义字段
+        template: Option<usize>, // 使用的模板在配置中的下标
     },
     PomodoroSettings {
         selected_duration: usize,
-        custom_duration: String,
+        custom_duration: TextInput,
         current_focus: usize,
     },
-    Search {
-        query: String,
-    },
     Settings {
         play_during_pomodoro: bool,
         play_on_finish: bool,
         current_focus: usize,
     },
+    Defer {
+        selected: usize,
+    },
+    Links {
+        selected: usize,
+    },
+    Templates {
+        selected: usize,
+    },
+    BulkDates {
+        selected: usize,
+    },
+    Confirm {
+        action: ConfirmAction,
+    },
+    Assignee {
+        selected: usize, // 0 为"未分配"，其余对应 roster 中的成员
+    },
+    Inbox {
+        task_id: u64, // 正在处理的任务
+        step: InboxStep,
+    },
+    MoveToList {
+        selected: usize, // 最后一项为收件箱
+    },
+    Input {
+        kind: InputKind,
+        value: TextInput,
+    },
     Empty,
 }
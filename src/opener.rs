@@ -0,0 +1,27 @@
+use std::io;
+use std::process::{Command, Stdio};
+
+/// 用系统默认程序（或配置的 opener）打开链接或文件，不等待其退出
+pub fn open(target: &str, opener: Option<&str>) -> io::Result<()> {
+    let mut command = match opener.filter(|opener| !opener.trim().is_empty()) {
+        Some(opener) => {
+            let mut parts = opener.split_whitespace();
+            let mut command = Command::new(parts.next().unwrap_or_default());
+            command.args(parts);
+            command
+        }
+        // 不经过 cmd，链接中的 & | ^ % 不会被当作命令解释
+        None if cfg!(windows) => Command::new("explorer"),
+        None if cfg!(target_os = "macos") => Command::new("open"),
+        None => Command::new("xdg-open"),
+    };
+
+    // 丢弃输出，避免打乱 TUI 画面
+    command
+        .arg(target)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
@@ -1,147 +1,307 @@
 use crate::app::App;
 use crate::dashboard::Dashboard;
-use crate::models::{ActiveWindow, PlaybackState, TaskStatus, TokyoNight, WindowData, WindowType};
+use crate::markdown;
+use crate::models::{
+    Action, ActiveWindow, AppMode, ConfirmAction, DETAILS_KEYS, DateShift, DeferPreset, Estimate,
+    FieldDef, HelpState, InboxStep, InputKind, MUSIC_LIST_KEYS, POMODORO_PRESETS, PlaybackState,
+    Priority, SortMode, TaskLink, TaskStatus, TaskTemplate, TextInput, TokyoNight, WindowData,
+    WindowType, format_datetime, format_duration, initials, match_ranges,
+};
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Layout, Margin, Position, Rect},
-    style::{Color, Style, Stylize},
+    layout::{Alignment, Constraint, Flex, Layout, Margin, Position, Rect},
+    style::{Color, Modifier, Style, Stylize},
     symbols::border,
-    text::{Line, Span},
-    widgets::{Block, Clear, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation},
+    text::{Line, Span, Text},
+    widgets::{
+        Block, Clear, Gauge, List, ListItem, ListState, Paragraph, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Wrap,
+    },
 };
 use time::OffsetDateTime;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 pub fn render(app: &App, frame: &mut Frame) {
     let area = frame.area();
 
     // 如果显示 dashboard，只渲染 dashboard
     if app.show_dashboard {
-        Dashboard::render(area, frame, "1.0.0");
+        Dashboard::render(area, frame, "1.0.0", &app.config.keymaps.normal);
         return;
     }
+    // 底部留一行作为状态栏
+    let [body, status_area] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(area);
+
     // 竖着进行分割, 分割成三份
     let main_layout = Layout::horizontal([
         Constraint::Percentage(15),
         Constraint::Percentage(55),
         Constraint::Percentage(30),
     ]);
-    let cols = main_layout.split(area);
+    let cols = main_layout.split(body);
 
     let right_layout = Layout::vertical([Constraint::Percentage(30), Constraint::Percentage(70)]);
     let right_areas = right_layout.split(cols[2]);
 
+    // 有今日计划的任务时，在列表上方显示"今日计划"区域
+    let now = OffsetDateTime::now_utc();
+    let scheduled_today = app
+        .tasks
+        .iter()
+        .filter(|task| task.is_scheduled_today(now))
+        .count();
+    let [today_area, list_area] = Layout::vertical([
+        Constraint::Length(if scheduled_today > 0 {
+            scheduled_today.min(5) as u16 + 2
+        } else {
+            0
+        }),
+        Constraint::Min(0),
+    ])
+    .areas(cols[1]);
+
     draw_search(app, cols[0], frame);
-    draw_todo_list(app, cols[1], frame);
+    draw_scheduled_today(app, today_area, frame);
+    draw_todo_list(app, list_area, frame);
     draw_pomodoro(app, right_areas[0], frame);
     draw_details(app, right_areas[1], frame);
+    draw_status_bar(app, status_area, frame);
 
     // 如果有活动窗口，渲染在顶层
     if let Some(window) = &app.active_window {
         draw_window(app, window, frame);
     }
-}
 
-fn draw_search(_app: &App, area: Rect, frame: &mut Frame) {
-    let block = Block::bordered()
-        .title(Line::from("🔍 Search ").centered())
-        .border_style(Style::default().fg(TokyoNight::MAGENTA))
-        .border_set(border::ROUNDED);
-    frame.render_widget(Paragraph::new("输入标签搜索...").block(block), area);
+    // 帮助浮层在最上层
+    if let Some(help) = &app.help {
+        draw_help(app, help, frame);
+    }
 }
 
-fn draw_todo(app: &App, area: Rect, title: &str, is_active: bool, frame: &mut Frame) {
-    let border_style = if is_active {
-        Style::default().fg(TokyoNight::CYAN).bold()
-    } else {
-        Style::default().fg(TokyoNight::RED)
-    };
-
-    let block = Block::bordered()
-        .title(Line::from("📝 新的todo ").centered())
-        .border_set(border::ROUNDED)
-        .border_style(border_style);
-
-    // 获取光标位置
-    let cursor_pos = if is_active {
-        if let Some(window) = &app.active_window {
-            if let WindowData::CreateTask {
-                cursor_position,
-                current_field,
-                ..
-            } = &window.data
-            {
-                if *current_field == 0 {
-                    Some(*cursor_position)
+fn draw_status_bar(app: &App, area: Rect, frame: &mut Frame) {
+    // 左侧显示当前模式
+    let (mode_name, mode_style) = app.mode.render_mode_line();
+    let badge = Span::styled(mode_name, mode_style);
+    let [mode_area, line_area] = Layout::horizontal([
+        Constraint::Length(badge.width() as u16),
+        Constraint::Fill(1),
+    ])
+    .areas(area);
+    frame.render_widget(Paragraph::new(badge), mode_area);
+
+    // 命令模式下状态栏就是命令行
+    if app.mode.is(AppMode::Command) {
+        let prompt = Span::styled(" :", Style::default().fg(AppMode::Command.color()));
+        // 有补全候选项时输入框只占输入内容的宽度，候选项列在之后
+        let input_width = match &app.completion {
+            Some(_) => Constraint::Length(app.prompt.text().width() as u16 + 1),
+            None => Constraint::Fill(1),
+        };
+        let [prompt_area, input_area, candidates_area] = Layout::horizontal([
+            Constraint::Length(prompt.width() as u16),
+            input_width,
+            Constraint::Fill(1),
+        ])
+        .areas(line_area);
+        frame.render_widget(Paragraph::new(prompt), prompt_area);
+        draw_text_input(input_area, &app.prompt, "", true, frame);
+
+        // 当前填入的候选项高亮
+        if let Some(completion) = &app.completion {
+            let mut spans = vec![Span::raw("  ")];
+            for (i, candidate) in completion.candidates.iter().enumerate() {
+                let style = if completion.selected == Some(i) {
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(AppMode::Command.color())
                 } else {
-                    None
-                }
-            } else {
-                None
+                    Style::default().fg(TokyoNight::GRAY)
+                };
+                spans.push(Span::styled(candidate.as_str(), style));
+                spans.push(Span::raw(" "));
             }
-        } else {
-            None
+            frame.render_widget(Paragraph::new(Line::from(spans)), candidates_area);
         }
-    } else {
-        None
-    };
+        return;
+    }
 
-    // 构建显示文本
-    let display_text = if title.is_empty() {
-        if is_active {
-            " ".to_string() // 空文本时显示一个空格让光标可见
-        } else {
-            "输入任务标题...".to_string()
+    let line = match &app.status_message {
+        Some(message) => {
+            let color = if message.is_error {
+                TokyoNight::RED
+            } else {
+                TokyoNight::CYAN
+            };
+            Line::from(Span::styled(
+                format!(" {}", message.text),
+                Style::default().fg(color),
+            ))
         }
+        // 显示当前模式的按键提示，从按键映射生成
+        None => {
+            let hints = app.config.keymaps.get(app.mode.current()).hints();
+            Line::from(
+                hints
+                    .into_iter()
+                    .flat_map(|(key, hint)| {
+                        [
+                            format!(" {key} ").fg(Color::Rgb(255, 200, 100)),
+                            hint.fg(TokyoNight::GRAY),
+                        ]
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        }
+    };
+    // 右侧留出位置显示正在录制的宏和尚未输入完整的按键序列
+    let mut pending = Line::default();
+    if let Some(name) = app.recording_register() {
+        pending.push_span(Span::styled(
+            format!(" 录制 @{name} "),
+            Style::default().fg(TokyoNight::RED).bold(),
+        ));
+    }
+    if !app.pending.is_empty() {
+        pending.push_span(Span::styled(
+            format!(" {} ", app.pending.display()),
+            Style::default().fg(Color::Rgb(255, 200, 100)).bold(),
+        ));
+    }
+    let pending_width = pending.width() as u16;
+    let [line_area, pending_area] =
+        Layout::horizontal([Constraint::Fill(1), Constraint::Length(pending_width)])
+            .areas(line_area);
+    frame.render_widget(Paragraph::new(line), line_area);
+    frame.render_widget(Paragraph::new(pending), pending_area);
+}
+
+fn draw_search(app: &App, area: Rect, frame: &mut Frame) {
+    let searching = app.mode.is(AppMode::Search);
+    let border_style = if searching {
+        Style::default().fg(AppMode::Search.color()).bold()
     } else {
-        title.to_string()
+        Style::default().fg(TokyoNight::MAGENTA)
     };
+    let block = Block::bordered()
+        .title(Line::from("🔍 Search ").centered())
+        .border_style(border_style)
+        .border_set(border::ROUNDED);
 
-    // 先设置光标位置（使用 display_text 的引用）
-    if let Some(pos) = cursor_pos {
-        // 计算光标的屏幕位置
-        let visible_start = if pos > area.width as usize - 3 {
-            pos.saturating_sub(area.width as usize - 3)
-        } else {
-            0
+    let matches = app.search_matches().len();
+    if !searching {
+        let text = match &app.search_query {
+            Some(query) => Text::from(vec![
+                Line::from(format!("/{query}")),
+                Line::from(format!("{matches} 项匹配").fg(TokyoNight::GRAY)),
+                Line::from("n/N 跳转 :noh 清除".fg(TokyoNight::GRAY)),
+            ]),
+            None => Text::from(
+                format!(
+                    "{} 搜索标题、描述、标签...",
+                    key_of(app, Action::SearchMode)
+                )
+                .fg(TokyoNight::GRAY),
+            ),
         };
-
-        let cursor_x = area.x + 1 + (pos - visible_start) as u16;
-        let cursor_y = area.y + 1;
-
-        if cursor_x < area.x + area.width - 1 {
-            frame.set_cursor_position(Position::new(cursor_x, cursor_y));
-        }
+        frame.render_widget(
+            Paragraph::new(text).wrap(Wrap { trim: true }).block(block),
+            area,
+        );
+        return;
     }
 
-    // 先渲染 Paragraph
-    let paragraph = Paragraph::new(display_text)
-        .block(block)
-        .style(if is_active {
-            Style::default().fg(Color::White).bg(TokyoNight::GRAY)
-        } else {
-            Style::default()
-        });
-
-    frame.render_widget(paragraph, area);
+    let inner = block.inner(area);
+    let count = if app.prompt.is_empty() {
+        Line::default()
+    } else if matches == 0 {
+        Line::from("没有匹配".fg(TokyoNight::RED))
+    } else {
+        Line::from(format!("{matches} 项匹配").fg(TokyoNight::GRAY))
+    };
+    frame.render_widget(
+        Paragraph::new(vec![Line::from("/"), count]).block(block),
+        area,
+    );
+    let input_area = Rect::new(inner.x + 1, inner.y, inner.width.saturating_sub(1), 1);
+    draw_text_input(input_area, &app.prompt, "", true, frame);
+}
 
-    // 再设置光标位置（渲染之后）
-    if let Some(pos) = cursor_pos {
-        let visible_start = if pos > area.width as usize - 3 {
-            pos.saturating_sub(area.width as usize - 3)
-        } else {
-            0
-        };
+/// 渲染文本输入框：按显示宽度横向滚动，选中的内容反色显示，`focused` 时显示光标
+fn draw_text_input(
+    area: Rect,
+    input: &TextInput,
+    placeholder: &str,
+    focused: bool,
+    frame: &mut Frame,
+) {
+    if area.is_empty() {
+        return;
+    }
+    let (width, height) = (area.width as usize, area.height as usize);
+    let (top, left) = input.scroll_to_cursor(width, height);
 
-        let cursor_x = area.x + 1 + (pos - visible_start) as u16;
-        let cursor_y = area.y + 1;
+    if input.is_empty() {
+        frame.render_widget(Paragraph::new(placeholder).fg(TokyoNight::GRAY), area);
+    } else {
+        let selection = input.selection().unwrap_or_default();
+        let mut line_start = 0;
+        let lines: Vec<Line> = input
+            .text()
+            .split('\n')
+            .map(|line| {
+                let start = line_start;
+                line_start += line.len() + 1;
+                (start, line)
+            })
+            .skip(top)
+            .take(height)
+            .map(|(start, line)| {
+                let mut spans: Vec<Span> = Vec::new();
+                let mut col = 0;
+                for (i, grapheme) in line.grapheme_indices(true) {
+                    let grapheme_width = grapheme.width();
+                    let end = col + grapheme_width;
+                    let text = if col >= left && end <= left + width {
+                        grapheme.to_string()
+                    } else if col < left && end > left {
+                        // 被左边缘截断的宽字符用空格补齐
+                        " ".repeat(end - left)
+                    } else {
+                        String::new()
+                    };
+                    col = end;
+                    if text.is_empty() {
+                        continue;
+                    }
+                    let style = if selection.contains(&(start + i)) {
+                        Style::default().add_modifier(Modifier::REVERSED)
+                    } else {
+                        Style::default()
+                    };
+                    // 样式相同的相邻字素合并到一个 Span
+                    match spans.last_mut() {
+                        Some(span) if span.style == style => span.content.to_mut().push_str(&text),
+                        _ => spans.push(Span::styled(text, style)),
+                    }
+                }
+                Line::from(spans)
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), area);
+    }
 
-        if cursor_x < area.x + area.width - 1 {
-            frame.set_cursor_position(Position::new(cursor_x, cursor_y));
+    if focused {
+        let (row, col) = input.cursor_position();
+        let (x, y) = ((col - left) as u16, (row - top) as u16);
+        if x < area.width && y < area.height {
+            frame.set_cursor_position(Position::new(area.x + x, area.y + y));
         }
     }
 }
 
-fn draw_desc(app: &App, area: Rect, description: &str, is_active: bool, frame: &mut Frame) {
+fn draw_todo(area: Rect, title: &TextInput, is_active: bool, frame: &mut Frame) {
     let border_style = if is_active {
         Style::default().fg(TokyoNight::CYAN).bold()
     } else {
@@ -149,95 +309,116 @@ fn draw_desc(app: &App, area: Rect, description: &str, is_active: bool, frame: &
     };
 
     let block = Block::bordered()
-        .title(Line::from("📋 todo的详细信息 ").centered())
+        .title(Line::from("📝 新的todo ").centered())
         .border_set(border::ROUNDED)
-        .border_style(border_style);
-
-    // 获取光标位置
-    let cursor_pos = if is_active {
-        if let Some(window) = &app.active_window {
-            if let WindowData::CreateTask {
-                cursor_position,
-                current_field,
-                ..
-            } = &window.data
-            {
-                if *current_field == 1 {
-                    Some(*cursor_position)
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
+        .border_style(border_style)
+        .style(if is_active {
+            Style::default().fg(Color::White).bg(TokyoNight::GRAY)
         } else {
-            None
-        }
+            Style::default()
+        });
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let placeholder = if is_active {
+        ""
     } else {
-        None
+        "输入任务标题..."
     };
+    draw_text_input(inner, title, placeholder, is_active, frame);
+}
 
-    // 构建显示文本
-    let display_text = if description.is_empty() {
-        if is_active {
-            " ".to_string()
-        } else {
-            "输入任务描述...".to_string()
-        }
+fn draw_desc(area: Rect, description: &TextInput, is_active: bool, frame: &mut Frame) {
+    let border_style = if is_active {
+        Style::default().fg(TokyoNight::CYAN).bold()
     } else {
-        description.to_string()
+        Style::default().fg(TokyoNight::RED)
     };
 
-    // 先计算光标位置（使用 display_text 的引用）
-    if let Some(pos) = cursor_pos {
-        let visible_start = if pos > area.width as usize - 3 {
-            pos.saturating_sub(area.width as usize - 3)
-        } else {
-            0
-        };
-
-        let cursor_x = area.x + 1 + (pos - visible_start) as u16;
-        let cursor_y = area.y + 1;
-
-        if cursor_x < area.x + area.width - 1 {
-            frame.set_cursor_position(Position::new(cursor_x, cursor_y));
-        }
-    }
-
-    // 先渲染 Paragraph
-    let paragraph = Paragraph::new(display_text)
-        .block(block)
+    let mut block = Block::bordered()
+        .title(Line::from("📋 todo的详细信息 ").centered())
+        .border_set(border::ROUNDED)
+        .border_style(border_style)
         .style(if is_active {
             Style::default().fg(Color::White).bg(TokyoNight::GRAY)
         } else {
             Style::default()
         });
+    if is_active {
+        block = block.title_bottom(
+            Line::from(vec![
+                " Alt-Enter ".fg(Color::Rgb(255, 200, 100)),
+                "换行 ".fg(Color::White),
+            ])
+            .centered(),
+        );
+    }
 
-    frame.render_widget(paragraph, area);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let placeholder = if is_active {
+        ""
+    } else {
+        "输入任务描述..."
+    };
+    draw_text_input(inner, description, placeholder, is_active, frame);
+}
 
-    // 再设置光标位置（渲染之后）
-    if let Some(pos) = cursor_pos {
-        let visible_start = if pos > area.width as usize - 3 {
-            pos.saturating_sub(area.width as usize - 3)
-        } else {
-            0
-        };
+/// 今日计划：今天开始的任务，尚未到开始时间的显示为灰色
+fn draw_scheduled_today(app: &App, area: Rect, frame: &mut Frame) {
+    if area.height == 0 {
+        return;
+    }
 
-        let cursor_x = area.x + 1 + (pos - visible_start) as u16;
-        let cursor_y = area.y + 1;
+    let now = OffsetDateTime::now_utc();
+    let mut tasks: Vec<_> = app
+        .tasks
+        .iter()
+        .filter(|task| task.is_scheduled_today(now))
+        .collect();
+    tasks.sort_by_key(|task| task.scheduled_date);
 
-        if cursor_x < area.x + area.width - 1 {
-            frame.set_cursor_position(Position::new(cursor_x, cursor_y));
-        }
-    }
+    let items: Vec<ListItem> = tasks
+        .into_iter()
+        .map(|task| {
+            let start = task.scheduled_date.unwrap_or(now);
+            let style = if task.is_actionable(now) {
+                Style::default()
+            } else {
+                Style::default().fg(TokyoNight::GRAY)
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!(" {:02}:{:02} ", start.hour(), start.minute()),
+                    Style::default().fg(TokyoNight::ORANGE),
+                ),
+                Span::styled(task.title.clone(), style),
+            ]))
+        })
+        .collect();
+
+    let block = Block::bordered()
+        .title(Line::from("📅 今日计划 ").centered())
+        .border_style(Style::default().fg(TokyoNight::ORANGE))
+        .border_set(border::ROUNDED);
+    frame.render_widget(List::new(items).block(block), area);
 }
 
 fn draw_todo_list(app: &App, area: Rect, frame: &mut Frame) {
+    // 可视模式下选中的行
+    let visual_rows = app.visual_rows().unwrap_or_default();
+    // 输入命令时总是显示行号，方便填写行范围
+    let numbers = app.show_numbers || app.mode.is(AppMode::Command);
+    let pattern = app.search_pattern();
+    let number_width = app.view.len().to_string().len();
+
     // 1. 使用 status 枚举获取图标
     let items: Vec<ListItem> = app
-        .tasks
+        .view
         .iter()
-        .map(|task| {
+        .enumerate()
+        .map(|(row, &i)| (row, &app.tasks[i]))
+        .map(|(row, task)| {
             // 使用 status.icon() 获取对应的图标
             let status_icon = task.status.icon();
 
@@ -253,7 +434,15 @@ fn draw_todo_list(app: &App, area: Rect, frame: &mut Frame) {
             let icon_span =
                 Span::styled(format!(" {status_icon} "), Style::default().fg(icon_color));
 
-            let title_span = Span::raw(task.title.clone());
+            // 高亮标题中匹配搜索关键字的部分
+            let mut title_spans = highlight_matches(&task.title, pattern);
+            // 只有描述、标签或备注匹配时给出提示
+            if pattern.is_some_and(|p| task.matches(p) && match_ranges(&task.title, p).is_empty()) {
+                title_spans.push(Span::styled(
+                    " 🔍",
+                    Style::default().fg(AppMode::Search.color()),
+                ));
+            }
 
             // 如果有截止日期，添加额外信息（可选）
             let due_info = if let Some(due) = task.due_date {
@@ -272,113 +461,932 @@ fn draw_todo_list(app: &App, area: Rect, frame: &mut Frame) {
 
             let due_span = Span::raw(due_info);
 
-            ListItem::new(Line::from(vec![icon_span, title_span, due_span]))
-        })
+            let mut spans = Vec::new();
+            if numbers {
+                spans.push(format!(" {:>number_width$}", row + 1).fg(TokyoNight::GRAY));
+            }
+            spans.push(icon_span);
+            if let Some(priority) = task.priority {
+                spans.push(Span::styled(
+                    format!("{} ", priority_marker(priority)),
+                    Style::default().fg(priority_color(priority)).bold(),
+                ));
+            }
+            spans.extend(title_spans);
+            spans.push(due_span);
+
+            // 情境
+            for context in &task.contexts {
+                spans.push(Span::styled(
+                    format!(" @{context}"),
+                    Style::default().fg(TokyoNight::CYAN),
+                ));
+            }
+
+            // 负责人缩写
+            if let Some(handle) = &task.assignee {
+                let initials = app
+                    .config
+                    .member(handle)
+                    .map_or_else(|| initials(handle), |member| member.initials());
+                let color = if app.config.user.as_ref() == Some(handle) {
+                    TokyoNight::ORANGE
+                } else {
+                    TokyoNight::MAGENTA
+                };
+                spans.push(Span::styled(
+                    format!(" [{initials}]"),
+                    Style::default().fg(color),
+                ));
+            }
+
+            // 进度条，5 格
+            if let Some(progress) = task.progress() {
+                let filled = (progress as usize + 10) / 20;
+                spans.push(Span::styled(
+                    format!(" {}{}", "▰".repeat(filled), "▱".repeat(5 - filled)),
+                    Style::default().fg(TokyoNight::CYAN),
+                ));
+                spans.push(Span::styled(
+                    format!(" {progress}%"),
+                    Style::default().fg(TokyoNight::GRAY),
+                ));
+            }
+
+            // 计时中 / 超出预估提示
+            if task.is_tracking() {
+                spans.push(Span::styled(" ⏱", Style::default().fg(TokyoNight::CYAN)));
+            }
+            if task.status != TaskStatus::Completed && task.is_over_estimate(app.pomodoro_minutes) {
+                spans.push(Span::styled(
+                    " ⚠ 超时",
+                    Style::default().fg(TokyoNight::RED),
+                ));
+            }
+
+            let item = ListItem::new(Line::from(spans));
+            if visual_rows.contains(&row) {
+                item.style(Style::default().bg(AppMode::Visual.bg_color()))
+            } else {
+                item
+            }
+        })
+        .collect();
+
+    let mut block = Block::bordered()
+        .title(Line::from("📝 Todo List ").centered())
+        .border_set(border::ROUNDED);
+
+    // 当前排序和筛选条件
+    if app.sort != SortMode::default() {
+        block = block.title(
+            Line::from(format!(" ↕ {} ", app.sort.label(&app.config.fields)).fg(TokyoNight::CYAN))
+                .right_aligned(),
+        );
+    }
+    let inbox = app.inbox().len();
+    if inbox > 0 {
+        block = block.title(
+            Line::from(
+                format!(" 📥 {inbox} ({} 处理) ", key_of(app, Action::ProcessInbox))
+                    .fg(TokyoNight::ORANGE),
+            )
+            .left_aligned(),
+        );
+    }
+    if !visual_rows.is_empty() {
+        block = block.title(
+            Line::from(format!(" 已选 {} 项 ", visual_rows.len()).fg(AppMode::Visual.color()))
+                .right_aligned(),
+        );
+    }
+    if app.only_mine {
+        block = block.title(Line::from(" 👤 我的 ".fg(TokyoNight::ORANGE)).left_aligned());
+    }
+    if let Some(filter) = &app.filter {
+        block = block
+            .title_bottom(Line::from(format!(" 🔎 {filter} ").fg(TokyoNight::CYAN)).left_aligned());
+    }
+
+    // 提示有多少尚未开始的任务被隐藏
+    let now = OffsetDateTime::now_utc();
+    let hidden = if app.show_future {
+        0
+    } else {
+        app.tasks
+            .iter()
+            .filter(|task| !task.is_actionable(now))
+            .count()
+    };
+    if hidden > 0 {
+        block = block.title_bottom(
+            Line::from(
+                format!(
+                    " {hidden} 个任务尚未开始 ({} 显示) ",
+                    key_of(app, Action::ToggleFuture)
+                )
+                .fg(TokyoNight::GRAY),
+            )
+            .right_aligned(),
+        );
+    }
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(TokyoNight::GRAY)
+                .fg(Color::White)
+                .bold(),
+        )
+        .highlight_symbol(">> ");
+
+    // 注意：这里需要传入可变引用的拷贝
+    frame.render_stateful_widget(list, area, &mut app.list_state.clone());
+
+    // 2. 渲染滚动条
+    let visible_height = area.height.saturating_sub(2) as usize;
+    if app.view.len() > visible_height {
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .track_symbol(Some("░"))
+            .thumb_symbol("█");
+
+        frame.render_stateful_widget(
+            scrollbar,
+            area.inner(Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut app.scroll_state.clone(),
+        );
+    }
+}
+
+fn draw_pomodoro(app: &App, area: Rect, frame: &mut Frame) {
+    let block = Block::bordered()
+        .title(Line::from("🍅 Pomodoro ").centered())
+        .border_style(Style::default().fg(TokyoNight::RED))
+        .border_set(border::ROUNDED);
+
+    let lines = match &app.pomodoro {
+        Some(session) => {
+            let remaining = session.remaining().whole_seconds().max(0);
+            let task_title = session
+                .task_index
+                .and_then(|i| app.tasks.get(i))
+                .map(|task| task.title.clone())
+                .unwrap_or_else(|| "未关联任务".to_string());
+            vec![
+                Line::from("专注中".fg(TokyoNight::ORANGE)),
+                Line::from(
+                    format!("{:02}:{:02}", remaining / 60, remaining % 60)
+                        .fg(Color::White)
+                        .bold(),
+                ),
+                Line::from(task_title.fg(TokyoNight::CYAN)),
+                Line::from(vec![
+                    format!("{} ", key_of(app, Action::FinishPomodoro))
+                        .fg(Color::Rgb(255, 100, 100))
+                        .bold(),
+                    "停止".fg(TokyoNight::GRAY),
+                ]),
+            ]
+        }
+        None => vec![
+            Line::from("番茄钟"),
+            Line::from(format!("{} 分钟", app.pomodoro_minutes).fg(TokyoNight::GRAY)),
+            Line::from(vec![
+                format!("{} ", key_of(app, Action::Pomodoro))
+                    .fg(Color::Rgb(100, 255, 100))
+                    .bold(),
+                "开始".fg(TokyoNight::GRAY),
+            ]),
+        ],
+    };
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .alignment(Alignment::Center);
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_details(app: &App, area: Rect, frame: &mut Frame) {
+    let border_style = if app.details_focused {
+        Style::default().fg(TokyoNight::CYAN).bold()
+    } else {
+        Style::default().fg(TokyoNight::GRAY)
+    };
+
+    let mut block = Block::bordered()
+        .title(Line::from("ℹ️ Info ").centered())
+        .border_style(border_style)
+        .border_set(border::ROUNDED);
+
+    let Some(task) = app.selected_task() else {
+        frame.render_widget(Paragraph::new("没有选中的任务").block(block), area);
+        return;
+    };
+
+    if app.details_focused {
+        block = block.title_bottom(Line::from(hint_spans(DETAILS_KEYS)).centered());
+    }
+
+    let label_style = Style::default().fg(TokyoNight::GRAY);
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(task.title.clone(), Style::default().bold()),
+            Span::styled(format!("  #{}", task.id), label_style),
+        ]),
+        Line::from(vec![
+            Span::styled("状态: ", label_style),
+            Span::raw(format!("{} {}", task.status.icon(), task.status.label())),
+        ]),
+    ];
+
+    if let Some(priority) = task.priority {
+        lines.push(Line::from(vec![
+            Span::styled("优先级: ", label_style),
+            Span::styled(
+                priority.label(),
+                Style::default().fg(priority_color(priority)),
+            ),
+        ]));
+    }
+
+    // 紧急度及各项得分，解释列表排名
+    if task.status != TaskStatus::Completed {
+        let urgency = app.urgency(task);
+        let mut spans = vec![
+            Span::styled("紧急度: ", label_style),
+            Span::styled(
+                format!("{:.1}", urgency.score),
+                Style::default().fg(TokyoNight::ORANGE).bold(),
+            ),
+        ];
+        if !urgency.factors.is_empty() {
+            let factors: Vec<String> = urgency
+                .factors
+                .iter()
+                .map(|(label, value)| format!("{label} {value:+.1}"))
+                .collect();
+            spans.push(Span::styled(
+                format!(" ({})", factors.join(", ")),
+                label_style,
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    if !task.tags.is_empty() {
+        let mut tags: Vec<&str> = task.tags.iter().map(|tag| tag.name()).collect();
+        tags.sort_unstable();
+
+        let mut spans = vec![Span::styled("标签: ", label_style)];
+        spans.extend(
+            tags.into_iter().map(|tag| {
+                Span::styled(format!("#{tag} "), Style::default().fg(TokyoNight::MAGENTA))
+            }),
+        );
+        lines.push(Line::from(spans));
+    }
+
+    lines.push(Line::from(vec![
+        Span::styled("清单: ", label_style),
+        match &task.list {
+            Some(list) => Span::raw(list.clone()),
+            None => Span::styled("📥 收件箱", Style::default().fg(TokyoNight::ORANGE)),
+        },
+    ]));
+    if !task.contexts.is_empty() {
+        let mut spans = vec![Span::styled("情境: ", label_style)];
+        spans.extend(task.contexts.iter().map(|context| {
+            Span::styled(
+                format!("@{context} "),
+                Style::default().fg(TokyoNight::CYAN),
+            )
+        }));
+        lines.push(Line::from(spans));
+    }
+    if let Some(handle) = &task.assignee {
+        lines.push(Line::from(vec![
+            Span::styled("负责人: ", label_style),
+            Span::raw(person_label(app, handle)),
+        ]));
+    }
+    let mut created = vec![
+        Span::styled("创建: ", label_style),
+        Span::raw(format_datetime(task.created_at)),
+    ];
+    if let Some(handle) = &task.created_by {
+        created.push(Span::styled(
+            format!(" 由 {}", person_label(app, handle)),
+            label_style,
+        ));
+    }
+    lines.push(Line::from(created));
+    if let Some(start) = task.scheduled_date {
+        lines.push(Line::from(vec![
+            Span::styled("开始: ", label_style),
+            Span::raw(format_datetime(start)),
+        ]));
+    }
+    if let Some(due) = task.due_date {
+        lines.push(Line::from(vec![
+            Span::styled("截止: ", label_style),
+            Span::raw(format_datetime(due)),
+        ]));
+    }
+    if let Some(finish) = task.finish_date {
+        lines.push(Line::from(vec![
+            Span::styled("完成: ", label_style),
+            Span::raw(format_datetime(finish)),
+        ]));
+    }
+
+    // 自定义字段按配置中的顺序显示
+    for def in &app.config.fields {
+        if let Some(value) = task.fields.get(&def.name) {
+            lines.push(Line::from(vec![
+                Span::styled(format!("{}: ", def.label()), label_style),
+                Span::raw(value.to_string()),
+            ]));
+        }
+    }
+
+    if task.estimate.is_some() || !task.actual_time().is_zero() {
+        let actual = format_duration(task.actual_time());
+        let actual_style = if task.is_over_estimate(app.pomodoro_minutes) {
+            Style::default().fg(TokyoNight::RED).bold()
+        } else {
+            Style::default()
+        };
+
+        let mut spans = vec![Span::styled("用时: ", label_style)];
+        spans.push(Span::styled(actual, actual_style));
+        if let Some(estimate) = task.estimate {
+            let minutes = estimate.minutes(app.pomodoro_minutes);
+            spans.push(Span::raw(format!(
+                " / 预估 {}",
+                format_duration(time::Duration::minutes(minutes.into()))
+            )));
+            if matches!(estimate, Estimate::Pomodoros(_)) {
+                spans.push(Span::styled(
+                    format!(" ({}/{})", task.pomodoros_done, estimate.label()),
+                    label_style,
+                ));
+            }
+        }
+        if task.is_tracking() {
+            spans.push(Span::styled(
+                " ⏱ 计时中",
+                Style::default().fg(TokyoNight::CYAN),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    if !task.links.is_empty() {
+        lines.push(Line::styled("链接:", label_style));
+        for link in &task.links {
+            lines.push(Line::from(vec![
+                Span::raw(format!("  {} ", link.icon())),
+                Span::styled(
+                    link_label(app, link),
+                    Style::default()
+                        .fg(TokyoNight::MAGENTA)
+                        .add_modifier(Modifier::UNDERLINED),
+                ),
+            ]));
+        }
+    }
+
+    lines.push(Line::styled(
+        "─".repeat(area.width.saturating_sub(2) as usize),
+        label_style,
+    ));
+
+    if task.description.trim().is_empty() {
+        lines.push(Line::styled("暂无描述", label_style));
+    } else {
+        let selected_checkbox = app.details_focused.then_some(app.checkbox_cursor);
+        lines.extend(markdown::render(&task.description, selected_checkbox));
+    }
+
+    if !task.comments.is_empty() {
+        lines.push(Line::styled(
+            "─".repeat(area.width.saturating_sub(2) as usize),
+            label_style,
+        ));
+        lines.push(Line::styled("💬 备注", label_style));
+        for comment in &task.comments {
+            lines.push(Line::from(vec![
+                Span::styled(format!("{} ", format_datetime(comment.at)), label_style),
+                Span::raw(comment.text.clone()),
+            ]));
+        }
+    }
+
+    // 历史时间线
+    lines.push(Line::styled(
+        "─".repeat(area.width.saturating_sub(2) as usize),
+        label_style,
+    ));
+    lines.push(Line::styled("🕘 历史", label_style));
+    for activity in &task.history {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{} ", format_datetime(activity.at)), label_style),
+            Span::raw(format!(
+                "{} {}",
+                activity.kind.icon(),
+                activity.kind.describe()
+            )),
+        ]));
+    }
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    // 有进度时在底部显示进度条
+    let text_area = match task.progress() {
+        Some(progress) => {
+            let [text_area, gauge_area] =
+                Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner);
+            let color = if progress == 100 {
+                Color::Green
+            } else {
+                TokyoNight::CYAN
+            };
+            let label = if task.manual_progress.is_some() {
+                format!("{progress}%")
+            } else {
+                format!("{progress}% (清单)")
+            };
+            let gauge = Gauge::default()
+                .gauge_style(Style::default().fg(color).bg(TokyoNight::GRAY))
+                .percent(progress.into())
+                .label(label);
+            frame.render_widget(gauge, gauge_area);
+            text_area
+        }
+        None => inner,
+    };
+
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, text_area);
+}
+
+/// 渲染窗口（覆盖在现有界面上）
+fn draw_window(_app: &App, window: &ActiveWindow, frame: &mut Frame) {
+    if !window.is_visible {
+        return;
+    }
+
+    // 创建窗口区域
+    let area = Rect::new(
+        window.layout.x,
+        window.layout.y,
+        window.layout.width,
+        window.layout.height,
+    );
+
+    // 根据窗口类型渲染不同内容
+    match (&window.window_type, &window.data) {
+        (WindowType::CreateTask, data @ WindowData::CreateTask { .. }) => {
+            draw_create_task_window(_app, area, data, frame);
+        }
+        (WindowType::PomodoroSettings, _) => {
+            draw_pomodoro_settings_window(_app, area, frame);
+        }
+        (WindowType::Settings, _) => {
+            draw_setting_windows(_app, area, frame);
+        }
+        (WindowType::Defer, WindowData::Defer { selected }) => {
+            draw_defer_window(area, *selected, frame);
+        }
+        (WindowType::Links, WindowData::Links { selected }) => {
+            draw_links_window(_app, area, *selected, frame);
+        }
+        (WindowType::Confirm, WindowData::Confirm { action }) => {
+            draw_confirm_window(_app, area, action, frame);
+        }
+        (WindowType::BulkDates, WindowData::BulkDates { selected }) => {
+            draw_bulk_dates_window(_app, area, *selected, frame);
+        }
+        (WindowType::Inbox, WindowData::Inbox { task_id, step }) => {
+            draw_inbox_window(_app, area, *task_id, step, frame);
+        }
+        (WindowType::MoveToList, WindowData::MoveToList { selected }) => {
+            draw_move_to_list_window(_app, area, *selected, frame);
+        }
+        (WindowType::Assignee, WindowData::Assignee { selected }) => {
+            draw_assignee_window(_app, area, *selected, frame);
+        }
+        (WindowType::Templates, WindowData::Templates { selected }) => {
+            draw_templates_window(_app, area, *selected, frame);
+        }
+        (WindowType::Input, WindowData::Input { kind, value }) => {
+            draw_input_window(area, *kind, value, frame);
+        }
+        _ => {}
+    }
+}
+
+/// 推迟开始日期的预设选择窗口
+fn draw_defer_window(area: Rect, selected: usize, frame: &mut Frame) {
+    frame.render_widget(Clear, area);
+
+    let block = Block::bordered()
+        .title(Line::from("⏰ 推迟到 ").centered())
+        .border_style(Style::default().fg(TokyoNight::ORANGE))
+        .border_set(border::ROUNDED)
+        .bg(Color::Rgb(20, 20, 40));
+
+    let items: Vec<ListItem> = DeferPreset::ALL
+        .iter()
+        .map(|preset| ListItem::new(format!(" {}", preset.label())))
+        .collect();
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(TokyoNight::GRAY)
+                .fg(Color::White)
+                .bold(),
+        )
+        .highlight_symbol("▶");
+
+    let mut state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+/// 成员的显示名称，不在 roster 中时只显示 handle
+fn person_label(app: &App, handle: &str) -> String {
+    match app.config.member(handle) {
+        Some(member) if member.name.is_some() => {
+            format!("{} (@{handle})", member.display_name())
+        }
+        _ => format!("@{handle}"),
+    }
+}
+
+/// 选择目标清单的窗口，最后一项为收件箱
+fn draw_move_to_list_window(app: &App, area: Rect, selected: usize, frame: &mut Frame) {
+    frame.render_widget(Clear, area);
+
+    let block = Block::bordered()
+        .title(Line::from("📁 移到清单 ").centered())
+        .border_style(Style::default().fg(TokyoNight::CYAN))
+        .border_set(border::ROUNDED)
+        .bg(Color::Rgb(20, 20, 40));
+
+    let mut items: Vec<ListItem> = app
+        .known_lists()
+        .into_iter()
+        .map(|list| ListItem::new(format!(" 📁 {list}")))
         .collect();
+    items.push(ListItem::new(Span::styled(
+        " 📥 收件箱",
+        Style::default().fg(TokyoNight::ORANGE),
+    )));
 
     let list = List::new(items)
-        .block(
-            Block::bordered()
-                .title(Line::from("📝 Todo List ").centered())
-                .border_set(border::ROUNDED),
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(TokyoNight::GRAY)
+                .fg(Color::White)
+                .bold(),
         )
+        .highlight_symbol("▶");
+
+    let mut state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+/// 选择负责人的窗口
+fn draw_assignee_window(app: &App, area: Rect, selected: usize, frame: &mut Frame) {
+    frame.render_widget(Clear, area);
+
+    let block = Block::bordered()
+        .title(Line::from("👤 分配给 ").centered())
+        .border_style(Style::default().fg(TokyoNight::MAGENTA))
+        .border_set(border::ROUNDED)
+        .bg(Color::Rgb(20, 20, 40));
+
+    let mut items = vec![ListItem::new(Span::styled(
+        " 未分配",
+        Style::default().fg(TokyoNight::GRAY),
+    ))];
+    items.extend(app.config.roster.iter().map(|member| {
+        let me = app.config.user.as_ref() == Some(&member.handle);
+        ListItem::new(Line::from(vec![
+            Span::styled(
+                format!(" [{}] ", member.initials()),
+                Style::default().fg(TokyoNight::MAGENTA),
+            ),
+            Span::raw(person_label(app, &member.handle)),
+            Span::styled(
+                if me { " (我)" } else { "" },
+                Style::default().fg(TokyoNight::ORANGE),
+            ),
+        ]))
+    }));
+
+    let list = List::new(items)
+        .block(block)
         .highlight_style(
             Style::default()
                 .bg(TokyoNight::GRAY)
                 .fg(Color::White)
                 .bold(),
         )
-        .highlight_symbol(">> ");
+        .highlight_symbol("▶");
 
-    // 注意：这里需要传入可变引用的拷贝
-    frame.render_stateful_widget(list, area, &mut app.list_state.clone());
+    let mut state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
 
-    // 2. 渲染滚动条
-    let visible_height = area.height.saturating_sub(2) as usize;
-    if app.tasks.len() > visible_height {
-        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
-            .track_symbol(Some("░"))
-            .thumb_symbol("█");
+fn priority_marker(priority: Priority) -> &'static str {
+    match priority {
+        Priority::High => "!!!",
+        Priority::Medium => "!!",
+        Priority::Low => "!",
+    }
+}
 
-        frame.render_stateful_widget(
-            scrollbar,
-            area.inner(Margin {
-                vertical: 1,
-                horizontal: 0,
-            }),
-            &mut app.scroll_state.clone(),
-        );
+fn priority_color(priority: Priority) -> Color {
+    match priority {
+        Priority::High => TokyoNight::RED,
+        Priority::Medium => TokyoNight::ORANGE,
+        Priority::Low => TokyoNight::CYAN,
+    }
+}
+
+/// 链接显示文本，任务链接附带对应任务的标题
+fn link_label(app: &App, link: &TaskLink) -> String {
+    let Some(id) = link.task_id() else {
+        return link.to_string();
+    };
+    let prefix = if matches!(link, TaskLink::BlockedBy(_)) {
+        "依赖 "
+    } else {
+        ""
+    };
+    match app.tasks.iter().find(|task| task.id == id) {
+        Some(task) => format!("{prefix}#{id} {}", task.title),
+        None => format!("{prefix}#{id} (不存在)"),
     }
 }
 
-fn draw_pomodoro(_app: &App, area: Rect, frame: &mut Frame) {
+/// 选中任务的链接列表，Enter 打开，d 删除
+fn draw_links_window(app: &App, area: Rect, selected: usize, frame: &mut Frame) {
+    frame.render_widget(Clear, area);
+
     let block = Block::bordered()
-        .title(Line::from("🍅 Pomodoro ").centered())
-        .border_style(Style::default().fg(TokyoNight::RED))
-        .border_set(border::ROUNDED);
+        .title(Line::from("🔗 链接 ").centered())
+        .title_bottom(
+            Line::from(vec![
+                " Enter ".fg(Color::Rgb(100, 255, 100)),
+                "打开 ".fg(Color::White),
+                "d ".fg(Color::Rgb(255, 100, 100)),
+                "删除 ".fg(Color::White),
+            ])
+            .centered(),
+        )
+        .border_style(Style::default().fg(TokyoNight::MAGENTA))
+        .border_set(border::ROUNDED)
+        .bg(Color::Rgb(20, 20, 40));
+
+    let items: Vec<ListItem> = app
+        .selected_task()
+        .map(|task| {
+            task.links
+                .iter()
+                .map(|link| ListItem::new(format!(" {} {}", link.icon(), link_label(app, link))))
+                .collect()
+        })
+        .unwrap_or_default();
 
-    let paragraph = Paragraph::new("番茄钟")
+    let list = List::new(items)
         .block(block)
-        .alignment(Alignment::Center);
-    frame.render_widget(paragraph, area);
+        .highlight_style(
+            Style::default()
+                .bg(TokyoNight::GRAY)
+                .fg(Color::White)
+                .bold(),
+        )
+        .highlight_symbol("▶");
+
+    let mut state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(list, area, &mut state);
 }
 
-fn draw_details(_app: &App, area: Rect, frame: &mut Frame) {
+/// 确认窗口，y / Enter 确认，n / Esc 取消
+fn draw_confirm_window(app: &App, area: Rect, action: &ConfirmAction, frame: &mut Frame) {
+    frame.render_widget(Clear, area);
+
+    let find_title = |id: u64| {
+        app.tasks
+            .iter()
+            .find(|task| task.id == id)
+            .map_or("", |task| task.title.as_str())
+    };
+    let question = match action {
+        ConfirmAction::DeleteTasks(ids) => match ids.as_slice() {
+            [id] => format!("删除「{}」？此操作无法撤销", find_title(*id)),
+            ids => format!("删除选中的 {} 个任务？此操作无法撤销", ids.len()),
+        },
+        ConfirmAction::CompleteTask(id) => {
+            format!("进度已达 100%，将「{}」标记为完成？", find_title(*id))
+        }
+    };
+
     let block = Block::bordered()
-        .title(Line::from("ℹ️ Info ").centered())
-        .border_style(Style::default().fg(TokyoNight::GRAY))
-        .border_set(border::ROUNDED);
+        .title(Line::from(" ❓ 确认 ").centered())
+        .title_bottom(
+            Line::from(vec![
+                " y ".fg(Color::Rgb(100, 255, 100)),
+                "确认 ".fg(Color::White),
+                "n ".fg(Color::Rgb(255, 100, 100)),
+                "取消 ".fg(Color::White),
+            ])
+            .centered(),
+        )
+        .border_style(Style::default().fg(Color::Green))
+        .border_set(border::ROUNDED)
+        .bg(Color::Rgb(20, 20, 40));
 
-    let paragraph = Paragraph::new("这里是任务的详细描述...").block(block);
-    frame.render_widget(paragraph, area);
+    frame.render_widget(Paragraph::new(question).block(block), area);
 }
 
-/// 渲染窗口（覆盖在现有界面上）
-fn draw_window(_app: &App, window: &ActiveWindow, frame: &mut Frame) {
-    if !window.is_visible {
-        return;
-    }
+/// 逐项处理收件箱：上方显示当前任务，下方显示当前步骤的操作
+fn draw_inbox_window(app: &App, area: Rect, task_id: u64, step: &InboxStep, frame: &mut Frame) {
+    frame.render_widget(Clear, area);
 
-    // 创建窗口区域
-    let area = Rect::new(
-        window.layout.x,
-        window.layout.y,
-        window.layout.width,
-        window.layout.height,
-    );
+    let block = Block::bordered()
+        .title(Line::from(format!(" 📥 处理收件箱（剩余 {}） ", app.inbox().len())).centered())
+        .title_bottom(Line::from(hint_spans(step.key_hints().iter().copied())).centered())
+        .border_style(Style::default().fg(TokyoNight::ORANGE))
+        .border_set(border::ROUNDED)
+        .bg(Color::Rgb(20, 20, 40));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
 
-    // 根据窗口类型渲染不同内容
-    match (&window.window_type, &window.data) {
-        (
-            WindowType::CreateTask,
-            WindowData::CreateTask {
-                title,
-                description,
-                current_field,
-                cursor_position,
+    let Some(task) = app.tasks.iter().find(|task| task.id == task_id) else {
+        return;
+    };
+
+    let [info_area, _, options_area] = Layout::vertical([
+        Constraint::Length(4),
+        Constraint::Length(1),
+        Constraint::Fill(1),
+    ])
+    .areas(inner);
+
+    let label_style = Style::default().fg(TokyoNight::GRAY);
+    let mut info = vec![Line::from(Span::styled(
+        format!(" {}", task.title),
+        Style::default().fg(Color::White).bold(),
+    ))];
+    if let Some(line) = task
+        .description
+        .lines()
+        .find(|line| !line.trim().is_empty())
+    {
+        info.push(Line::from(Span::styled(format!(" {line}"), label_style)));
+    }
+    let contexts: Vec<String> = task.contexts.iter().map(|c| format!("@{c}")).collect();
+    info.push(Line::from(vec![
+        Span::styled(" 情境: ", label_style),
+        Span::styled(
+            if contexts.is_empty() {
+                "无".to_string()
+            } else {
+                contexts.join(" ")
             },
-        ) => {
-            draw_create_task_window(_app, area, title, description, *current_field, frame);
+            Style::default().fg(TokyoNight::CYAN),
+        ),
+    ]));
+    info.push(Line::from(vec![
+        Span::styled(" 截止: ", label_style),
+        Span::raw(task.due_date.map_or("无".to_string(), format_datetime)),
+    ]));
+    frame.render_widget(Paragraph::new(info), info_area);
+
+    let highlight = Style::default()
+        .bg(TokyoNight::GRAY)
+        .fg(Color::White)
+        .bold();
+    match step {
+        InboxStep::Menu => {}
+        InboxStep::List { selected } => {
+            let items: Vec<ListItem> = app
+                .known_lists()
+                .into_iter()
+                .map(|list| ListItem::new(format!(" 📁 {list}")))
+                .collect();
+            let list = List::new(items)
+                .highlight_style(highlight)
+                .highlight_symbol("▶");
+            let mut state = ListState::default().with_selected(Some(*selected));
+            frame.render_stateful_widget(list, options_area, &mut state);
         }
-        (WindowType::PomodoroSettings, _) => {
-            draw_pomodoro_settings_window(_app, area, frame);
+        InboxStep::Context { selected } => {
+            let items: Vec<ListItem> = app
+                .config
+                .contexts()
+                .into_iter()
+                .map(|context| {
+                    let mark = if task.contexts.contains(&context) {
+                        "[x]"
+                    } else {
+                        "[ ]"
+                    };
+                    ListItem::new(format!(" {mark} @{context}"))
+                })
+                .collect();
+            let list = List::new(items)
+                .highlight_style(highlight)
+                .highlight_symbol("▶");
+            let mut state = ListState::default().with_selected(Some(*selected));
+            frame.render_stateful_widget(list, options_area, &mut state);
         }
-        (WindowType::Settings, _) => {
-            draw_setting_windows(_app, area, frame);
+        InboxStep::Due { value } => {
+            let input_area = Rect {
+                height: 1,
+                ..options_area.inner(Margin::new(1, 0))
+            };
+            draw_text_input(
+                input_area,
+                value,
+                "例如 2025-06-01 18:00 或 3d，留空清除",
+                true,
+                frame,
+            );
         }
-        _ => {}
     }
 }
 
+/// 批量调整截止日期的操作列表，作用于当前列表中的所有任务
+fn draw_bulk_dates_window(app: &App, area: Rect, selected: usize, frame: &mut Frame) {
+    frame.render_widget(Clear, area);
+
+    let block = Block::bordered()
+        .title(Line::from(format!(" 📆 批量调整 {} 个任务 ", app.target_count())).centered())
+        .border_style(Style::default().fg(TokyoNight::ORANGE))
+        .border_set(border::ROUNDED)
+        .bg(Color::Rgb(20, 20, 40));
+
+    let items: Vec<ListItem> = DateShift::ALL
+        .iter()
+        .map(|shift| ListItem::new(format!(" {}", shift.label())))
+        .collect();
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(TokyoNight::GRAY)
+                .fg(Color::White)
+                .bold(),
+        )
+        .highlight_symbol("▶");
+
+    let mut state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+/// 通用单行输入窗口
+fn draw_input_window(area: Rect, kind: InputKind, value: &TextInput, frame: &mut Frame) {
+    frame.render_widget(Clear, area);
+
+    let block = Block::bordered()
+        .title(Line::from(kind.title()).centered())
+        .border_style(Style::default().fg(TokyoNight::CYAN))
+        .border_set(border::ROUNDED)
+        .bg(Color::Rgb(20, 20, 40));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    draw_text_input(inner, value, kind.placeholder(), true, frame);
+}
+
 /// 创建任务窗口
-fn draw_create_task_window(
-    _app: &App,
-    area: Rect,
-    title: &str,
-    description: &str,
-    current_field: usize,
-    frame: &mut Frame,
-) {
+fn draw_create_task_window(_app: &App, area: Rect, data: &WindowData, frame: &mut Frame) {
+    let WindowData::CreateTask {
+        title,
+        description,
+        fields,
+        current_field,
+        template,
+    } = data
+    else {
+        return;
+    };
+    let current_field = *current_field;
+    let template = template.and_then(|i| _app.config.templates.get(i));
+
     // 先清除区域（创建半透明遮罩效果）
     let clear_block = Block::default();
     frame.render_widget(Clear, area);
@@ -403,18 +1411,125 @@ fn draw_create_task_window(
     let left_layout = Layout::vertical([Constraint::Percentage(30), Constraint::Percentage(70)]);
     let left_areas = left_layout.split(chunks[0]);
 
-    draw_todo(_app, left_areas[0], title, current_field == 0, frame);
-    draw_desc(_app, left_areas[1], description, current_field == 1, frame);
-    draw_tag(_app, chunks[1], frame);
+    draw_todo(left_areas[0], title, current_field == 0, frame);
+    draw_desc(left_areas[1], description, current_field == 1, frame);
+
+    // 右侧上方为自定义字段，每个字段一个输入框，下方为模板信息
+    let mut constraints = vec![Constraint::Length(3); fields.len()];
+    constraints.push(Constraint::Min(0));
+    let right_areas = Layout::vertical(constraints).split(chunks[1]);
+    for (i, (field, input)) in _app.config.fields.iter().zip(fields).enumerate() {
+        draw_field_input(right_areas[i], field, input, current_field == i + 2, frame);
+    }
+    draw_tag(_app, right_areas[fields.len()], template, frame);
 }
 
-fn draw_tag(_app: &App, area: Rect, frame: &mut Frame) {
+/// 新建任务窗口中自定义字段的输入框，占位文字提示字段的格式
+fn draw_field_input(
+    area: Rect,
+    field: &FieldDef,
+    input: &TextInput,
+    is_active: bool,
+    frame: &mut Frame,
+) {
+    let border_style = if is_active {
+        Style::default().fg(TokyoNight::CYAN).bold()
+    } else {
+        Style::default().fg(TokyoNight::GRAY)
+    };
+
     let block = Block::bordered()
+        .title(Line::from(format!("{} ", field.label())))
+        .border_set(border::ROUNDED)
+        .border_style(border_style);
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    draw_text_input(inner, input, &field.hint(), is_active, frame);
+}
+
+fn draw_tag(_app: &App, area: Rect, template: Option<&TaskTemplate>, frame: &mut Frame) {
+    let mut block = Block::bordered()
         .title(Line::from("自定义标签 ").centered())
         .border_set(border::ROUNDED)
         .border_style(Style::default().fg(TokyoNight::ORANGE));
 
-    frame.render_widget(block, area);
+    // 使用模板时显示创建后会应用的标签和截止日期
+    let Some(template) = template else {
+        block = block.title_bottom(
+            Line::from(vec![
+                " Ctrl-T ".fg(Color::Rgb(255, 200, 100)),
+                "模板 ".fg(Color::White),
+                " Ctrl-O ".fg(Color::Rgb(255, 200, 100)),
+                "编辑器 ".fg(Color::White),
+            ])
+            .centered(),
+        );
+        frame.render_widget(block, area);
+        return;
+    };
+
+    let label_style = Style::default().fg(TokyoNight::GRAY);
+    let mut lines = vec![Line::from(vec![
+        Span::styled("模板: ", label_style),
+        Span::raw(template.name.clone()),
+    ])];
+    lines.extend(
+        template
+            .tags
+            .iter()
+            .map(|tag| Line::styled(format!("#{tag}"), Style::default().fg(TokyoNight::MAGENTA))),
+    );
+    if let Some(due) = &template.due {
+        lines.push(Line::from(vec![
+            Span::styled("截止: ", label_style),
+            Span::raw(format!("{due} 后")),
+        ]));
+    }
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+/// 新建任务的模板列表
+fn draw_templates_window(app: &App, area: Rect, selected: usize, frame: &mut Frame) {
+    frame.render_widget(Clear, area);
+
+    let block = Block::bordered()
+        .title(Line::from("📋 从模板新建 ").centered())
+        .border_style(Style::default().fg(TokyoNight::CYAN))
+        .border_set(border::ROUNDED)
+        .bg(Color::Rgb(20, 20, 40));
+
+    let items: Vec<ListItem> = app
+        .config
+        .templates
+        .iter()
+        .map(|template| {
+            let tags: Vec<String> = template.tags.iter().map(|tag| format!("#{tag}")).collect();
+            ListItem::new(Line::from(vec![
+                Span::raw(format!(" {} ", template.name)),
+                Span::styled(tags.join(" "), Style::default().fg(TokyoNight::MAGENTA)),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(TokyoNight::GRAY)
+                .fg(Color::White)
+                .bold(),
+        )
+        .highlight_symbol("▶");
+
+    let mut state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(list, area, &mut state);
 }
 
 fn draw_pomodoro_settings_window(_app: &App, area: Rect, frame: &mut Frame) {
@@ -482,13 +1597,15 @@ fn draw_commonly_used_pomodoro_time(
         .border_set(border::ROUNDED)
         .border_style(border_style);
 
-    let durations = ["15分钟", "20分钟", "25分钟", "30分钟", "45分钟"];
-    let items: Vec<ListItem> = durations
+    let items: Vec<ListItem> = POMODORO_PRESETS
         .iter()
         .enumerate()
-        .map(|(i, d)| {
+        .map(|(i, minutes)| {
             let prefix = if i == selected { " ▶ " } else { "  " };
-            ListItem::new(Line::from(vec![prefix.into(), (*d).into()]))
+            ListItem::new(Line::from(vec![
+                prefix.into(),
+                format!("{minutes}分钟").into(),
+            ]))
         })
         .collect();
 
@@ -499,7 +1616,7 @@ fn draw_commonly_used_pomodoro_time(
 fn draw_custom_pomodoro_time(
     _app: &App,
     area: Rect,
-    custom: &str,
+    custom: &TextInput,
     is_active: bool,
     frame: &mut Frame,
 ) {
@@ -512,24 +1629,22 @@ fn draw_custom_pomodoro_time(
     let block = Block::bordered()
         .title(Line::from("✏️ 自定义时间(分钟) ").centered())
         .border_set(border::ROUNDED)
-        .border_style(border_style);
-
-    let display_text = if custom.is_empty() {
-        "输入数字..."
-    } else {
-        custom
-    };
-
-    let paragraph = Paragraph::new(display_text)
-        .block(block)
-        .alignment(Alignment::Center)
+        .border_style(border_style)
         .style(if is_active {
             Style::default().fg(Color::White).bg(TokyoNight::GRAY)
         } else {
             Style::default()
         });
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
 
-    frame.render_widget(paragraph, area);
+    // 输入框居中显示
+    let placeholder = "输入数字...";
+    let width = custom.text().width().max(placeholder.width()) + 1;
+    let [input_area] = Layout::horizontal([Constraint::Length(width as u16)])
+        .flex(Flex::Center)
+        .areas(inner);
+    draw_text_input(input_area, custom, placeholder, is_active, frame);
 }
 
 fn draw_music_list(app: &App, area: Rect, is_active: bool, frame: &mut Frame) {
@@ -544,16 +1659,9 @@ fn draw_music_list(app: &App, area: Rect, is_active: bool, frame: &mut Frame) {
         .border_set(border::ROUNDED)
         .border_style(border_style);
 
-    // 显示播放状态提示 - 彩虹色
+    // 显示播放状态提示
     let help_text = if is_active {
-        Line::from(vec![
-            " ↑/k ↓/j ".fg(Color::Rgb(255, 200, 100)), // 暖黄
-            "选择 ".fg(Color::White),
-            "Enter ".fg(Color::Rgb(100, 255, 100)), // 亮绿
-            "播放 ".fg(Color::White),
-            "Space ".fg(Color::Rgb(100, 200, 255)), // 天蓝
-            "暂停/继续 ".fg(Color::White),
-        ])
+        Line::from(hint_spans(MUSIC_LIST_KEYS))
     } else {
         Line::from("")
     };
@@ -732,14 +1840,7 @@ fn draw_music_list_in_settings(app: &App, area: Rect, is_active: bool, frame: &m
 
     // 显示播放状态提示
     let help_text = if is_active {
-        Line::from(vec![
-            " ↑/k ↓/j ".fg(Color::Rgb(255, 200, 100)),
-            "选择  |  ".fg(Color::White),
-            "Enter ".fg(Color::Rgb(100, 255, 100)),
-            "播放  |  ".fg(Color::White),
-            "Space ".fg(Color::Rgb(100, 200, 255)),
-            "暂停/继续".fg(Color::White),
-        ])
+        Line::from(hint_spans(MUSIC_LIST_KEYS))
     } else {
         Line::from("")
     };
@@ -836,3 +1937,145 @@ fn draw_music_list_in_settings(app: &App, area: Rect, is_active: bool, frame: &m
         );
     }
 }
+
+/// 把文本拆成若干段，匹配 `pattern` 的部分高亮
+fn highlight_matches<'a>(text: &'a str, pattern: Option<&str>) -> Vec<Span<'a>> {
+    let ranges = pattern.map(|p| match_ranges(text, p)).unwrap_or_default();
+    let mut spans = Vec::new();
+    let mut last = 0;
+    for range in ranges {
+        if range.start > last {
+            spans.push(Span::raw(&text[last..range.start]));
+        }
+        spans.push(Span::styled(
+            &text[range.clone()],
+            Style::default()
+                .fg(Color::Black)
+                .bg(AppMode::Search.color()),
+        ));
+        last = range.end;
+    }
+    if last < text.len() || spans.is_empty() {
+        spans.push(Span::raw(&text[last..]));
+    }
+    spans
+}
+
+/// 按键提示：按键和说明交替着色，与状态栏的提示一致
+fn hint_spans<'a>(hints: impl IntoIterator<Item = (&'a str, &'a str)>) -> Vec<Span<'a>> {
+    hints
+        .into_iter()
+        .flat_map(|(key, hint)| {
+            [
+                format!(" {key} ").fg(Color::Rgb(255, 200, 100)),
+                hint.fg(TokyoNight::GRAY),
+            ]
+        })
+        .chain([Span::raw(" ")])
+        .collect()
+}
+
+/// 帮助浮层：列出当前模式和窗口的所有按键，`/` 输入的搜索词只保留匹配的按键
+fn draw_help(app: &App, help: &HelpState, frame: &mut Frame) {
+    let [area] = Layout::horizontal([Constraint::Percentage(70)])
+        .flex(Flex::Center)
+        .areas(frame.area());
+    let [area] = Layout::vertical([Constraint::Percentage(80)])
+        .flex(Flex::Center)
+        .areas(area);
+    frame.render_widget(Clear, area);
+
+    let query = help.query.text();
+    let sections: Vec<_> = app
+        .help_sections()
+        .into_iter()
+        .filter_map(|section| section.filter(query))
+        .collect();
+
+    let mut title = String::from(" ❓ 帮助 ");
+    if !query.is_empty() {
+        let count: usize = sections.iter().map(|section| section.entries.len()).sum();
+        title.push_str(&format!("· 匹配 {count} 项 "));
+    }
+    let block = Block::bordered()
+        .title(Line::from(title).centered())
+        .title_bottom(Line::from(hint_spans([("/", "搜索"), ("q", "关闭")])).centered())
+        .border_style(Style::default().fg(TokyoNight::CYAN))
+        .border_set(border::ROUNDED)
+        .bg(Color::Rgb(20, 20, 40));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    // 有搜索词时底部显示搜索行
+    let show_search = help.searching || !query.is_empty();
+    let [list_area, search_area] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(show_search as u16)])
+            .areas(inner.inner(Margin::new(1, 0)));
+
+    // 按键列对齐到最宽的按键
+    let key_width = sections
+        .iter()
+        .flat_map(|section| &section.entries)
+        .map(|(keys, _)| keys.width())
+        .max()
+        .unwrap_or(0);
+    let pattern = (!query.is_empty()).then_some(query);
+    let mut lines: Vec<Line> = Vec::new();
+    for section in &sections {
+        if !lines.is_empty() {
+            lines.push(Line::default());
+        }
+        lines.push(Line::styled(
+            section.title.as_str(),
+            Style::default().fg(TokyoNight::CYAN).bold(),
+        ));
+        for (keys, description) in &section.entries {
+            let mut spans = vec![Span::raw("  ")];
+            spans.extend(
+                highlight_matches(keys, pattern)
+                    .into_iter()
+                    .map(|span| span.patch_style(Style::default().fg(Color::Rgb(255, 200, 100)))),
+            );
+            spans.push(Span::raw(" ".repeat(key_width - keys.width() + 2)));
+            spans.extend(highlight_matches(description, pattern));
+            lines.push(Line::from(spans));
+        }
+    }
+    if lines.is_empty() {
+        lines.push(Line::styled(
+            "没有匹配的按键",
+            Style::default().fg(TokyoNight::GRAY),
+        ));
+    }
+
+    let height = list_area.height as usize;
+    let scroll = help.scroll_for(lines.len(), height);
+    let total = lines.len();
+    frame.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), list_area);
+    if total > height {
+        let mut state = ScrollbarState::new(total.saturating_sub(height)).position(scroll);
+        frame.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .track_symbol(Some("░"))
+                .thumb_symbol("█"),
+            area.inner(Margin::new(0, 1)),
+            &mut state,
+        );
+    }
+
+    if show_search {
+        let [prompt_area, input_area] =
+            Layout::horizontal([Constraint::Length(2), Constraint::Min(0)]).areas(search_area);
+        frame.render_widget(Paragraph::new("/").fg(AppMode::Search.color()), prompt_area);
+        draw_text_input(input_area, &help.query, "", help.searching, frame);
+    }
+}
+
+/// 普通模式中动作的按键，用于界面上的提示
+fn key_of(app: &App, action: Action) -> String {
+    app.config
+        .keymaps
+        .normal
+        .key_for(action)
+        .unwrap_or_else(|| "-".to_string())
+}